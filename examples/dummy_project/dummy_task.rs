@@ -1,7 +1,7 @@
-/// ==============================================================================
-/// examples/dummy_project/dummy_task.rs
-/// Dummy task spec wrapper.
-/// ==============================================================================
+//! ==============================================================================
+//! examples/dummy_project/dummy_task.rs
+//! Dummy task spec wrapper.
+//! ==============================================================================
 
 use std::thread;
 use std::time::Duration;
@@ -13,6 +13,8 @@ use rand::Rng;
 pub struct DummyTask {
     pub label: String,
     pub total_iters: u64,
+    /// Time spent sleeping so far; the task's output.
+    pub slept: Duration,
 }
 
 impl DummyTask {
@@ -24,17 +26,21 @@ impl DummyTask {
         Self {
             label: label.into(),
             total_iters,
+            slept: Duration::ZERO,
         }
     }
 
     /// Do one unit of work.
     pub fn step(&mut self) {
-        let delay_secs = rand::rng().random_range(1..=2);
-        thread::sleep(Duration::from_secs(delay_secs));
+        let delay = Duration::from_secs(rand::rng().random_range(1..=2));
+        thread::sleep(delay);
+        self.slept += delay;
     }
 }
 
 impl logger_bro::Task for DummyTask {
+    type Output = Duration;
+
     fn label(&self) -> &str {
         &self.label
     }
@@ -46,4 +52,8 @@ impl logger_bro::Task for DummyTask {
     fn workload_per_iter(&mut self) {
         self.step();
     }
+
+    fn finish(self) -> Duration {
+        self.slept
+    }
 }
@@ -1,7 +1,7 @@
-/// ==============================================================================
-/// examples/dummy_project/dummy_task_group.rs
-/// Dummy task group that owns a vector of dummy tasks.
-/// ==============================================================================
+//! ==============================================================================
+//! examples/dummy_project/dummy_task_group.rs
+//! Dummy task group that owns a vector of dummy tasks.
+//! ==============================================================================
 
 use super::dummy_task::DummyTask;
 
@@ -1,7 +1,7 @@
-/// ==============================================================================
-/// examples/dummy_project/main.rs
-/// End-to-end dummy runner using the dummy task group launcher.
-/// ==============================================================================
+//! ==============================================================================
+//! examples/dummy_project/main.rs
+//! End-to-end dummy runner using the dummy task group launcher.
+//! ==============================================================================
 mod dummy_task;
 mod dummy_task_group;
 
@@ -9,5 +9,12 @@ use logger_bro::TaskGroup;
 use dummy_task_group::DummyTaskGroup;
 
 fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    DummyTaskGroup::new(4, 20).launch()
+    let outcome = DummyTaskGroup::new(4, 20).launch()?;
+    for (label, output) in outcome.outputs {
+        match output {
+            Ok(slept) => println!("{label}: slept {slept:?}"),
+            Err(err) => println!("{label}: {err}"),
+        }
+    }
+    Ok(())
 }
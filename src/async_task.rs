@@ -0,0 +1,330 @@
+//! ==============================================================================
+//! src/async_task.rs
+//! Async task trait and tokio launcher for I/O-bound workloads.
+//! ==============================================================================
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use tokio::task::JoinHandle;
+
+use crate::launcher::{skip, task_id, FinishClock, Finished};
+use crate::retry::catch_panic;
+use crate::task_group::Description;
+use crate::{ClientReporter, LaunchOutcome, Launcher, RetryPolicy, TaskError, TaskId, TaskStatus};
+
+/// A single unit of async work executed on a tokio runtime.
+///
+/// This mirrors `Task`, but the per-iteration workload is a future so data
+/// fetchers and async writers can await I/O without blocking a thread.
+pub trait AsyncTask: Send + 'static {
+    /// Human-readable label for the task.
+    fn label(&self) -> &str;
+
+    /// Total iterations for this task.
+    fn total_iters(&self) -> u64;
+
+    /// Perform one unit of work.
+    fn workload_per_iter(&mut self) -> impl Future<Output = ()> + Send;
+}
+
+impl Launcher {
+    /// Run `tasks` on the current tokio runtime until each completes, fails,
+    /// or is canceled, and return how each ended and the report.
+    ///
+    /// Must be awaited from within a tokio runtime. The TUI is driven by
+    /// `Runtime::run_async`, so it shares the executor with the tasks. Every
+    /// option applies as for `launch`; with `concurrency`, at most that many
+    /// tasks are polled at a time. Tasks still queued when the TUI ends are
+    /// reported as never started.
+    pub async fn launch_async<T: AsyncTask>(
+        mut self,
+        tasks: Vec<T>,
+    ) -> Result<LaunchOutcome<()>, Box<dyn std::error::Error + Send + Sync>> {
+        let task_count = tasks.len();
+        let clock = FinishClock::start();
+        let (reporter, mut store) = self.open_unbounded_store();
+        let queue = Arc::new(Mutex::new(
+            tasks.into_iter().enumerate().collect::<VecDeque<_>>(),
+        ));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Shared rather than returned by each worker, so outputs survive a
+        // worker that panics.
+        let finished = Arc::new(Mutex::new(Vec::with_capacity(task_count)));
+
+        let workers = self.concurrency.unwrap_or(task_count).min(task_count);
+        let handles: Vec<JoinHandle<()>> = (0..workers)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let stop = Arc::clone(&stop);
+                let finished = Arc::clone(&finished);
+                let reporter = reporter.clone();
+                let retry = self.retry;
+                let ids = self.ids;
+                let clock = clock.clone();
+                tokio::spawn(async move {
+                    while !stop.load(Ordering::Relaxed) {
+                        let next = queue.lock().ok().and_then(|mut queue| queue.pop_front());
+                        let Some((index, task)) = next else {
+                            break;
+                        };
+                        let id = task_id(ids, index);
+                        let output = run_async_task(task, &reporter, &retry, id).await;
+                        clock.record();
+                        if let Ok(mut finished) = finished.lock() {
+                            finished.push((index, output));
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let outcome = self
+            .supervise_async(&mut store, task_count, &stop, || {
+                handles.iter().all(JoinHandle::is_finished)
+            })
+            .await;
+        for handle in handles {
+            let _ = handle.await;
+        }
+
+        let mut outputs: Vec<Finished<()>> =
+            std::mem::take(&mut *finished.lock().unwrap_or_else(|err| err.into_inner()));
+        let mut skipped = Vec::new();
+        if let Ok(mut queue) = queue.lock() {
+            for (index, task) in queue.drain(..) {
+                skip(index, describe_async(&task), &mut outputs, &mut skipped);
+            }
+        }
+        self.finish(store, &clock, task_count, outputs, skipped, outcome)
+    }
+}
+
+/// Helper that spawns async tasks on the current tokio runtime with standard
+/// TUI behavior, and returns how each ended and the report once every task
+/// has finished.
+///
+/// This is shorthand for `Launcher::new().fps(runtime_fps).launch_async(tasks)`
+/// with an optional label; use `Launcher` for every other option.
+pub async fn launch_async_tasks<T: AsyncTask>(
+    tasks: Vec<T>,
+    runtime_fps: u64,
+    runtime_project_label: Option<String>,
+) -> Result<LaunchOutcome<()>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut launcher = Launcher::new().fps(runtime_fps);
+    if let Some(label) = runtime_project_label {
+        launcher = launcher.label(label);
+    }
+    launcher.launch_async(tasks).await
+}
+
+/// Read the label and total iterations of `task` as `describe` does for a
+/// `Task`; async tasks have no parameters.
+fn describe_async<T: AsyncTask>(task: &T) -> Result<Description, String> {
+    catch_panic(|| (task.label().to_string(), task.total_iters(), Vec::new()))
+}
+
+/// Run a single async task to completion, reporting progress, and return its
+/// label and output.
+///
+/// An iteration that panics is retried as `retry` allows; after that, the
+/// panic message is logged and the client fails, as in `run_task`. Updates
+/// wait for a full bounded store by yielding, since the executor thread may
+/// be the one that drains it.
+async fn run_async_task<T: AsyncTask>(
+    task: T,
+    reporter: &ClientReporter,
+    retry: &RetryPolicy,
+    id: Option<TaskId>,
+) -> (String, Result<(), TaskError>) {
+    match describe_async(&task) {
+        Ok((label, total_iters, _)) => {
+            let output = run_described_async(task, &label, total_iters, reporter, retry, id).await;
+            (label, output)
+        }
+        Err(message) => (String::new(), Err(TaskError::Panicked(message))),
+    }
+}
+
+/// Run an async task whose `describe_async` succeeded; see `run_async_task`.
+async fn run_described_async<T: AsyncTask>(
+    mut task: T,
+    label: &str,
+    total_iters: u64,
+    reporter: &ClientReporter,
+    retry: &RetryPolicy,
+    id: Option<TaskId>,
+) -> Result<(), TaskError> {
+    let label = label.to_string();
+    let Ok(client) = reporter.start_async(id, label, Some(total_iters)).await else {
+        return Err(TaskError::NotStarted);
+    };
+    for step in 0..total_iters {
+        let _ = client.checkpoint_async().await;
+        if client.cancel_requested() {
+            let _ = client.set_status_async(TaskStatus::Canceled).await;
+            return Err(TaskError::Canceled);
+        }
+        let mut retries = 0;
+        while let Err(message) = CatchPanic(Box::pin(task.workload_per_iter())).await {
+            let iteration = step.saturating_add(1);
+            if retries == retry.max_retries() {
+                let _ = client
+                    .log_async(format!("iteration {iteration} panicked: {message}"))
+                    .await;
+                let _ = client.set_status_async(TaskStatus::Failed).await;
+                return Err(TaskError::Panicked(message));
+            }
+            retries += 1;
+            let _ = client
+                .log_async(format!(
+                    "iteration {iteration} panicked: {message}; retry {retries}/{}",
+                    retry.max_retries()
+                ))
+                .await;
+            tokio::time::sleep(retry.delay()).await;
+        }
+        let _ = client
+            .set_progress_async(step.saturating_add(1), None)
+            .await;
+        // A workload that is always ready would otherwise keep the TUI and
+        // every other task on this executor from running.
+        tokio::task::yield_now().await;
+    }
+    let _ = client
+        .set_progress_async(total_iters, Some(total_iters))
+        .await;
+    let _ = client.set_status_async(TaskStatus::Completed).await;
+    Ok(())
+}
+
+/// Future that resolves to the message of a panic while polling the inner
+/// future, instead of unwinding through the executor.
+struct CatchPanic<F>(F);
+
+impl<F: Future + Unpin> Future for CatchPanic<F> {
+    type Output = Result<F::Output, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match catch_panic(|| Pin::new(&mut self.0).poll(cx)) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(message) => Poll::Ready(Err(message)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{Backpressure, ClientStore, Renderer, TaskReport};
+
+    /// Async task whose iterations complete without ever waiting.
+    struct Ready(u64);
+
+    impl AsyncTask for Ready {
+        fn label(&self) -> &str {
+            "ready"
+        }
+
+        fn total_iters(&self) -> u64 {
+            self.0
+        }
+
+        async fn workload_per_iter(&mut self) {}
+    }
+
+    /// Async task that records its label in a shared log on every iteration.
+    struct Logged {
+        label: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl AsyncTask for Logged {
+        fn label(&self) -> &str {
+            self.label
+        }
+
+        fn total_iters(&self) -> u64 {
+            3
+        }
+
+        async fn workload_per_iter(&mut self) {
+            if let Ok(mut log) = self.log.lock() {
+                log.push(self.label);
+            }
+        }
+    }
+
+    /// Launch `tasks` headless on a single-threaded runtime, failing instead
+    /// of hanging if the launch never finishes.
+    fn launch_on_current_thread(
+        launcher: impl FnOnce() -> Launcher + Send + 'static,
+        tasks: Vec<Ready>,
+    ) -> LaunchOutcome<()> {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_time()
+                .build()
+                .expect("tokio runtime");
+            let launch = launcher().renderer(Renderer::Headless).launch_async(tasks);
+            let _ = tx.send(runtime.block_on(launch).map_err(|err| err.to_string()));
+        });
+        rx.recv_timeout(Duration::from_secs(30))
+            .expect("launch did not finish")
+            .expect("launch failed")
+    }
+
+    #[test]
+    fn ready_tasks_finish_on_a_current_thread_runtime() {
+        let outcome = launch_on_current_thread(Launcher::new, vec![Ready(20_000)]);
+
+        assert_eq!(outcome.outputs, [("ready".to_string(), Ok(()))]);
+        assert_eq!(outcome.report.tasks[0].iterations, 20_000);
+    }
+
+    #[test]
+    fn a_full_bounded_store_does_not_block_the_executor() {
+        let launcher = || {
+            let (reporter, store) = ClientStore::bounded(16, Backpressure::DropProgress);
+            Launcher::new().store(reporter, store)
+        };
+        let outcome = launch_on_current_thread(launcher, vec![Ready(20_000), Ready(20_000)]);
+
+        assert_eq!(outcome.report.tasks.len(), 2);
+        assert!(outcome.report.tasks.iter().all(TaskReport::succeeded));
+        assert!(outcome.outputs.iter().all(|(_, output)| output.is_ok()));
+    }
+
+    #[test]
+    fn ready_tasks_yield_between_iterations() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let tasks = ["a", "b"].map(|label| Logged {
+            label,
+            log: Arc::clone(&log),
+        });
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("tokio runtime");
+        let launch = Launcher::new()
+            .renderer(Renderer::Headless)
+            .launch_async(tasks.into());
+        runtime.block_on(launch).expect("launch failed");
+
+        let log = log.lock().expect("log").clone();
+        let first_b = log.iter().position(|label| *label == "b").expect("b ran");
+        let last_a = log.iter().rposition(|label| *label == "a").expect("a ran");
+        assert!(first_b < last_a, "tasks ran one after the other: {log:?}");
+    }
+}
@@ -0,0 +1,62 @@
+//! ==============================================================================
+//! src/client_control.rs
+//! Control flags sent from the runtime back to a client's worker.
+//! ==============================================================================
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+
+/// Pause and cancellation flags shared between a client's handle and the
+/// store.
+///
+/// The store sets the flags; the worker observes them at its next
+/// `ClientHandle::checkpoint`.
+#[derive(Debug, Default)]
+pub(crate) struct ClientControl {
+    paused: Mutex<bool>,
+    resumed: Condvar,
+    canceled: AtomicBool,
+}
+
+impl ClientControl {
+    /// Request a pause, or release a paused worker.
+    pub(crate) fn set_paused(&self, paused: bool) {
+        if let Ok(mut flag) = self.paused.lock() {
+            *flag = paused;
+        }
+        if !paused {
+            self.resumed.notify_all();
+        }
+    }
+
+    /// Return whether a pause is currently requested.
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.lock().map(|flag| *flag).unwrap_or(false)
+    }
+
+    /// Request cancellation, waking the worker if it is paused.
+    pub(crate) fn cancel(&self) {
+        // Set the flag under the lock so a worker about to wait cannot miss it.
+        let _guard = self.paused.lock();
+        self.canceled.store(true, Ordering::Relaxed);
+        self.resumed.notify_all();
+    }
+
+    /// Return whether cancellation has been requested.
+    pub(crate) fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Relaxed)
+    }
+
+    /// Block the calling thread until the pause is lifted or the client is
+    /// canceled.
+    pub(crate) fn wait_while_paused(&self) {
+        let Ok(flag) = self.paused.lock() else {
+            return;
+        };
+        // The guard is released as soon as the wait ends.
+        drop(
+            self.resumed
+                .wait_while(flag, |paused| *paused && !self.is_canceled()),
+        );
+    }
+}
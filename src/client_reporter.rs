@@ -1,13 +1,26 @@
-/// ==============================================================================
-/// src/client_reporter.rs
-/// Client-side reporting API for sending progress updates to the store.
-/// ==============================================================================
+//! ==============================================================================
+//! src/client_reporter.rs
+//! Client-side reporting API for sending progress updates to the store.
+//! ==============================================================================
 
-use std::sync::mpsc::Sender;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{Sender, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use std::time::Instant;
+use crate::client_control::ClientControl;
+use crate::client_store::StoreMessage;
+use crate::{ClientState, IdAllocation, TaskId, TaskStatus};
 
-use crate::{ClientState, TaskId, TaskStatus};
+/// How often `checkpoint_async` re-checks a paused client.
+#[cfg(feature = "tokio")]
+const ASYNC_PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    /// Client entered on this thread via `ClientHandle::enter`, if any.
+    static CURRENT_CLIENT: RefCell<Option<ClientHandle>> = const { RefCell::new(None) };
+}
 
 /// Errors that can occur when sending updates from a client thread.
 #[derive(Debug)]
@@ -16,13 +29,38 @@ pub enum ReportError {
     Closed,
 }
 
+/// What a bounded store channel does when it is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Block the sending thread until the store drains.
+    Block,
+    /// Drop progress-only updates (`set_current`) and block for everything
+    /// else, so labels, totals, and status changes are never lost.
+    DropProgress,
+}
+
 /// Lightweight client-side handle for sending updates into the store.
 ///
 /// This type is intended to be cloned and moved across threads without
 /// carrying any store internals.
 #[derive(Clone)]
 pub struct ClientReporter {
-    tx: Sender<ClientState>,
+    tx: Transport,
+    ids: IdAllocation,
+    /// Next sequential id; shared by every clone, so counted per store.
+    next_id: Arc<AtomicU64>,
+}
+
+/// Sending side of the store channel.
+#[derive(Clone)]
+enum Transport {
+    Unbounded(Sender<StoreMessage>),
+    Bounded {
+        tx: SyncSender<StoreMessage>,
+        policy: Backpressure,
+        /// Count of updates dropped under `Backpressure::DropProgress`.
+        dropped: Arc<AtomicU64>,
+    },
 }
 
 /// Handle for a single client/task instance.
@@ -34,20 +72,149 @@ pub struct ClientHandle {
     reporter: ClientReporter,
     id: TaskId,
     start_time: Instant,
+    /// Pause requests from the runtime, shared with the store.
+    control: Arc<ClientControl>,
 }
 
 impl ClientReporter {
     /// Create a reporter from a sender that feeds the store.
-    pub fn new(tx: Sender<ClientState>) -> Self {
-        Self { tx }
+    pub(crate) fn new(tx: Sender<StoreMessage>) -> Self {
+        Self {
+            tx: Transport::Unbounded(tx),
+            ids: IdAllocation::default(),
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Create a reporter from a bounded sender with a backpressure policy.
+    ///
+    /// `dropped` is shared with the store so it can expose how many updates
+    /// were discarded.
+    pub(crate) fn bounded(
+        tx: SyncSender<StoreMessage>,
+        policy: Backpressure,
+        dropped: Arc<AtomicU64>,
+    ) -> Self {
+        Self {
+            tx: Transport::Bounded {
+                tx,
+                policy,
+                dropped,
+            },
+            ids: IdAllocation::default(),
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Choose how clients started from now on get their ids.
+    ///
+    /// Clones made afterwards keep the choice and share the sequential
+    /// counter.
+    pub fn with_ids(mut self, ids: IdAllocation) -> Self {
+        self.ids = ids;
+        self
     }
 
     /// Send a raw `ClientState` update to the store.
     ///
     /// This is the lowest-level API; most users should prefer `start`
-    /// and the `ClientHandle` methods.
+    /// and the `ClientHandle` methods. On a bounded store with
+    /// `Backpressure::DropProgress`, a progress-only update is silently
+    /// dropped when the channel is full.
     pub fn report(&self, state: ClientState) -> Result<(), ReportError> {
-        self.tx.send(state).map_err(|_| ReportError::Closed)
+        self.send(StoreMessage::State(state))
+    }
+
+    /// Send any store message, applying the backpressure policy.
+    fn send(&self, message: StoreMessage) -> Result<(), ReportError> {
+        self.try_send(message).map(|_| ())
+    }
+
+    /// Send any store message, applying the backpressure policy, and return
+    /// whether it was queued rather than dropped.
+    fn try_send(&self, message: StoreMessage) -> Result<bool, ReportError> {
+        match &self.tx {
+            Transport::Bounded {
+                tx,
+                policy: Backpressure::DropProgress,
+                dropped,
+            } if is_progress_only(&message) => match tx.try_send(message) {
+                Ok(()) => Ok(true),
+                Err(TrySendError::Full(_)) => {
+                    dropped.fetch_add(1, Ordering::Relaxed);
+                    Ok(false)
+                }
+                Err(TrySendError::Disconnected(_)) => Err(ReportError::Closed),
+            },
+            _ => self.send_reliably(message).map(|()| true),
+        }
+    }
+
+    /// Send a store message that must not be dropped, blocking on a full
+    /// bounded channel whatever the backpressure policy.
+    fn send_reliably(&self, message: StoreMessage) -> Result<(), ReportError> {
+        match &self.tx {
+            Transport::Unbounded(tx) => tx.send(message).map_err(|_| ReportError::Closed),
+            Transport::Bounded { tx, .. } => tx.send(message).map_err(|_| ReportError::Closed),
+        }
+    }
+
+    /// Send a store message from async code, applying the backpressure
+    /// policy, but yield to the executor while a bounded channel is full
+    /// instead of blocking its thread, which may be the one that drains it.
+    ///
+    /// `reliable` messages are never dropped, as with `send_reliably`.
+    #[cfg(feature = "tokio")]
+    async fn send_async(
+        &self,
+        mut message: StoreMessage,
+        reliable: bool,
+    ) -> Result<(), ReportError> {
+        let Transport::Bounded {
+            tx,
+            policy,
+            dropped,
+        } = &self.tx
+        else {
+            return self.send_reliably(message);
+        };
+        let droppable =
+            !reliable && *policy == Backpressure::DropProgress && is_progress_only(&message);
+        loop {
+            match tx.try_send(message) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(_)) if droppable => {
+                    dropped.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(TrySendError::Full(returned)) => {
+                    message = returned;
+                    tokio::task::yield_now().await;
+                }
+                Err(TrySendError::Disconnected(_)) => return Err(ReportError::Closed),
+            }
+        }
+    }
+
+    /// Append a message to the store's global log buffer.
+    ///
+    /// Use `ClientHandle::log` for messages that belong to a single client.
+    pub fn log(&self, message: impl Into<String>) -> Result<(), ReportError> {
+        self.send(StoreMessage::Log(message.into()))
+    }
+
+    /// Append a message to the store's global log without blocking, and
+    /// return whether it was queued.
+    ///
+    /// Fails when the store is gone or its bounded channel is full, so
+    /// loggers can print the message elsewhere instead of losing it.
+    #[cfg(feature = "log")]
+    pub(crate) fn try_log(&self, message: &str) -> bool {
+        let message = StoreMessage::Log(message.to_string());
+        match &self.tx {
+            Transport::Unbounded(tx) => tx.send(message).is_ok(),
+            Transport::Bounded { tx, .. } => tx.try_send(message).is_ok(),
+        }
     }
 
     /// Start a new client/task and return a handle for future updates.
@@ -59,14 +226,88 @@ impl ClientReporter {
         label: impl Into<String>,
         total: Option<u64>,
     ) -> Result<ClientHandle, ReportError> {
-        let state = ClientState::new(label, total);
-        let handle = ClientHandle {
+        let label = label.into();
+        self.start_with_id(self.allocate_id(&label), label, total)
+    }
+
+    /// Start a new client/task with an id of the caller's choosing, such as
+    /// `TaskId::from_key` of its parameters.
+    ///
+    /// Reusing the id of a running client merges the two in the store.
+    pub fn start_with_id(
+        &self,
+        id: TaskId,
+        label: impl Into<String>,
+        total: Option<u64>,
+    ) -> Result<ClientHandle, ReportError> {
+        let mut state = ClientState::new(label, total);
+        state.id = id;
+        let handle = self.handle_for(&state);
+        self.report(state)?;
+        self.send(StoreMessage::Control {
+            id: handle.id,
+            control: Arc::clone(&handle.control),
+        })?;
+        Ok(handle)
+    }
+
+    /// Start a client from async code, like `start_with_id`, or like `start`
+    /// when `id` is `None`, without blocking on a full bounded store.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn start_async(
+        &self,
+        id: Option<TaskId>,
+        label: String,
+        total: Option<u64>,
+    ) -> Result<ClientHandle, ReportError> {
+        let id = id.unwrap_or_else(|| self.allocate_id(&label));
+        let mut state = ClientState::new(label, total);
+        state.id = id;
+        let handle = self.handle_for(&state);
+        self.send_async(StoreMessage::State(state), true).await?;
+        let control = StoreMessage::Control {
+            id: handle.id,
+            control: Arc::clone(&handle.control),
+        };
+        self.send_async(control, true).await?;
+        Ok(handle)
+    }
+
+    /// Start a new client/task whose progress lives in a shared atomic counter.
+    ///
+    /// The counter is registered with the store, which reads it directly on
+    /// `drain` and `snapshot`, so bumping progress never touches the channel.
+    pub fn start_atomic(
+        &self,
+        label: impl Into<String>,
+        total: Option<u64>,
+    ) -> Result<AtomicHandle, ReportError> {
+        let handle = self.start(label, total)?;
+        let counter = Arc::new(AtomicU64::new(0));
+        self.send(StoreMessage::Counter {
+            id: handle.id,
+            counter: Arc::clone(&counter),
+        })?;
+        Ok(AtomicHandle { handle, counter })
+    }
+
+    /// Pick the id of a new client labeled `label`, as set by `with_ids`.
+    fn allocate_id(&self, label: &str) -> TaskId {
+        match self.ids {
+            IdAllocation::Random => TaskId::new(),
+            IdAllocation::Sequential => TaskId(self.next_id.fetch_add(1, Ordering::Relaxed)),
+            IdAllocation::Label => TaskId::from_key(label),
+        }
+    }
+
+    /// Create the handle of a client whose initial state is `state`.
+    fn handle_for(&self, state: &ClientState) -> ClientHandle {
+        ClientHandle {
             reporter: self.clone(),
             id: state.id,
             start_time: state.start_time,
-        };
-        self.report(state)?;
-        Ok(handle)
+            control: Arc::new(ClientControl::default()),
+        }
     }
 }
 
@@ -92,6 +333,65 @@ impl ClientHandle {
         self.reporter.report(update)
     }
 
+    /// Begin a new phase of work with its own `total`, such as equilibration
+    /// followed by production.
+    ///
+    /// Progress restarts from zero; the previous phase is kept in
+    /// `ClientState::phases` with its progress and duration.
+    pub fn begin_phase(
+        &self,
+        name: impl Into<String>,
+        total: Option<u64>,
+    ) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.phase = Some(name.into());
+        update.total = total;
+        update.current = Some(0);
+        self.reporter.report(update)
+    }
+
+    /// Set how many phases this client/task will run, so the TUI can show
+    /// "phase 2/4" and weigh progress across phases.
+    pub fn set_phase_count(&self, count: usize) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.phase_count = Some(count);
+        self.reporter.report(update)
+    }
+
+    /// Return the client entered on the current thread, if any.
+    pub fn current() -> Option<ClientHandle> {
+        CURRENT_CLIENT.with(|current| current.borrow().clone())
+    }
+
+    /// Record the calling OS thread as the worker running this client/task.
+    ///
+    /// This lets the runtime attribute per-thread CPU usage to the client.
+    /// It is a no-op where the thread id cannot be determined (non-Linux).
+    pub fn attach_current_thread(&self) -> Result<(), ReportError> {
+        let Some(thread_id) = os_thread_id() else {
+            return Ok(());
+        };
+        let mut update = self.base_update();
+        update.thread_id = Some(thread_id);
+        self.reporter.report(update)
+    }
+
+    /// Make this the current client of the calling thread until the guard
+    /// is dropped.
+    ///
+    /// Log backends use this to route records emitted on a worker thread to
+    /// the client running there. The launchers enter each task's handle.
+    pub fn enter(&self) -> ClientGuard {
+        let previous = CURRENT_CLIENT.with(|current| current.replace(Some(self.clone())));
+        ClientGuard { previous }
+    }
+
+    /// Wrap this handle so progress is published at most as often as
+    /// `policy` allows.
+    pub fn coalesced(self, policy: CoalescePolicy) -> CoalescedHandle {
+        CoalescedHandle::new(self, policy)
+    }
+
     /// Set the current completed units of work.
     pub fn set_current(&self, current: u64) -> Result<(), ReportError> {
         let mut update = self.base_update();
@@ -99,6 +399,149 @@ impl ClientHandle {
         self.reporter.report(update)
     }
 
+    /// Set the current completed units and the total in a single update.
+    ///
+    /// Unlike `set_current`, this update is never dropped by a bounded store,
+    /// even without a total; a full store blocks it until there is room. This
+    /// makes it suitable for reporting final progress.
+    pub fn set_progress(&self, current: u64, total: Option<u64>) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.current = Some(current);
+        update.total = total;
+        self.reporter.send_reliably(StoreMessage::State(update))
+    }
+
+    /// Attach a log message to this client/task.
+    pub fn log(&self, message: impl Into<String>) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.messages.push(message.into());
+        self.reporter.report(update)
+    }
+
+    /// Set a named metric for this client/task, such as a loss or energy.
+    ///
+    /// The latest value of each metric is shown in its own column of the
+    /// runtime's client table.
+    pub fn set_metric(&self, name: impl Into<String>, value: f64) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.metrics.push((name.into(), value));
+        self.reporter.report(update)
+    }
+
+    /// Attach named parameters to this client/task, such as its point in a
+    /// parameter sweep.
+    ///
+    /// The runtime can sort clients by any of them.
+    pub fn set_params(
+        &self,
+        params: impl IntoIterator<Item = (String, f64)>,
+    ) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.params.extend(params);
+        self.reporter.report(update)
+    }
+
+    /// Block while the runtime has this client paused.
+    ///
+    /// Call this between units of work; `launch_tasks` calls it before every
+    /// iteration. While blocked the client reports `TaskStatus::Paused`, and
+    /// `Running` again once resumed. A cancellation request ends the wait;
+    /// check `cancel_requested` afterwards.
+    pub fn checkpoint(&self) -> Result<(), ReportError> {
+        if !self.control.is_paused() || self.control.is_canceled() {
+            return Ok(());
+        }
+        self.set_status(TaskStatus::Paused)?;
+        self.control.wait_while_paused();
+        if self.control.is_canceled() {
+            return Ok(());
+        }
+        self.set_status(TaskStatus::Running)
+    }
+
+    /// Wait while the runtime has this client paused, without blocking the
+    /// executor thread.
+    ///
+    /// The async counterpart of `checkpoint`, for tasks on a tokio runtime;
+    /// `Launcher::launch_async` calls it before every iteration.
+    #[cfg(feature = "tokio")]
+    pub async fn checkpoint_async(&self) -> Result<(), ReportError> {
+        if !self.control.is_paused() || self.control.is_canceled() {
+            return Ok(());
+        }
+        self.set_status_async(TaskStatus::Paused).await?;
+        while self.control.is_paused() && !self.control.is_canceled() {
+            tokio::time::sleep(ASYNC_PAUSE_POLL_INTERVAL).await;
+        }
+        if self.control.is_canceled() {
+            return Ok(());
+        }
+        self.set_status_async(TaskStatus::Running).await
+    }
+
+    /// Set a status from async code without blocking on a full bounded store.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn set_status_async(&self, status: TaskStatus) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.status = Some(status);
+        self.reporter
+            .send_async(StoreMessage::State(update), true)
+            .await
+    }
+
+    /// Set progress from async code without blocking on a full bounded store.
+    ///
+    /// A tick without a total may be dropped under
+    /// `Backpressure::DropProgress`, like `set_current`; with a total it
+    /// never is.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn set_progress_async(
+        &self,
+        current: u64,
+        total: Option<u64>,
+    ) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.current = Some(current);
+        update.total = total;
+        self.reporter
+            .send_async(StoreMessage::State(update), total.is_some())
+            .await
+    }
+
+    /// Attach a log message from async code without blocking on a full
+    /// bounded store.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn log_async(&self, message: String) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.messages.push(message);
+        self.reporter
+            .send_async(StoreMessage::State(update), true)
+            .await
+    }
+
+    /// Return whether the runtime asked this client to stop.
+    ///
+    /// Workers should stop at the next safe point and call `cancel`; the
+    /// launchers check this after every checkpoint.
+    pub fn cancel_requested(&self) -> bool {
+        self.control.is_canceled()
+    }
+
+    /// Mark this client/task as setting up, before its first iteration.
+    pub fn setting_up(&self) -> Result<(), ReportError> {
+        self.set_status(TaskStatus::SettingUp)
+    }
+
+    /// Mark this client/task as running its iterations.
+    pub fn running(&self) -> Result<(), ReportError> {
+        self.set_status(TaskStatus::Running)
+    }
+
+    /// Mark this client/task as finalizing, after its last iteration.
+    pub fn finalizing(&self) -> Result<(), ReportError> {
+        self.set_status(TaskStatus::Finalizing)
+    }
+
     /// Mark this client/task as completed.
     pub fn complete(&self) -> Result<(), ReportError> {
         self.set_status(TaskStatus::Completed)
@@ -121,6 +564,31 @@ impl ClientHandle {
         self.reporter.report(update)
     }
 
+    /// Set the final progress and a status in a single update, which a
+    /// bounded store never drops.
+    fn set_status_at(&self, status: TaskStatus, current: u64) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.current = Some(current);
+        update.status = Some(status);
+        self.reporter.report(update)
+    }
+
+    /// Send a progress tick, returning whether it was queued rather than
+    /// dropped by a full bounded store.
+    fn try_set_current(&self, current: u64) -> Result<bool, ReportError> {
+        let mut update = self.base_update();
+        update.current = Some(current);
+        self.reporter.try_send(StoreMessage::State(update))
+    }
+
+    /// Send a progress tick that is never dropped, even by a full store with
+    /// `Backpressure::DropProgress`.
+    fn set_current_reliably(&self, current: u64) -> Result<(), ReportError> {
+        let mut update = self.base_update();
+        update.current = Some(current);
+        self.reporter.send_reliably(StoreMessage::State(update))
+    }
+
     /// Construct a minimal update payload with identity and timestamps.
     ///
     /// The store will merge this partial state with the existing one.
@@ -128,3 +596,304 @@ impl ClientHandle {
         ClientState::partial(self.id, self.start_time, Instant::now())
     }
 }
+
+/// Guard returned by `ClientHandle::enter`; restores the previous current
+/// client of the thread when dropped.
+pub struct ClientGuard {
+    previous: Option<ClientHandle>,
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_CLIENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Return the kernel id of the calling thread, as listed in `/proc/self/task`.
+fn os_thread_id() -> Option<u64> {
+    // `/proc/thread-self` links to `<pid>/task/<tid>`.
+    let link = std::fs::read_link("/proc/thread-self").ok()?;
+    link.file_name()?.to_str()?.parse().ok()
+}
+
+/// Return whether a message only carries a progress tick.
+fn is_progress_only(message: &StoreMessage) -> bool {
+    match message {
+        StoreMessage::State(state) => {
+            state.label.is_none()
+                && state.status.is_none()
+                && state.total.is_none()
+                && state.phase.is_none()
+                && state.phase_count.is_none()
+                && state.thread_id.is_none()
+                && state.messages.is_empty()
+                && state.metrics.is_empty()
+                && state.params.is_empty()
+        }
+        StoreMessage::Counter { .. } | StoreMessage::Control { .. } | StoreMessage::Log(_) => {
+            false
+        }
+    }
+}
+
+/// Thresholds controlling when a `CoalescedHandle` publishes progress.
+///
+/// An update is sent once either threshold is reached since the previous
+/// publish. With neither set, every change is published.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoalescePolicy {
+    /// Minimum time between two progress updates.
+    pub interval: Option<Duration>,
+    /// Minimum number of steps between two progress updates.
+    pub steps: Option<u64>,
+}
+
+impl CoalescePolicy {
+    /// Publish at most once per `interval`.
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval: Some(interval),
+            steps: None,
+        }
+    }
+
+    /// Publish once at least `steps` units have accumulated.
+    pub fn every_steps(steps: u64) -> Self {
+        Self {
+            interval: None,
+            steps: Some(steps),
+        }
+    }
+
+    /// Also publish once at least `steps` units have accumulated.
+    pub fn or_steps(mut self, steps: u64) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+}
+
+/// Client handle that accumulates progress in shared atomics and publishes
+/// it according to a `CoalescePolicy`.
+///
+/// Clones share the same counters, so one handle can be driven from many
+/// threads. Pending progress is sent along with a final status, and flushed
+/// when the last clone is dropped.
+#[derive(Clone)]
+pub struct CoalescedHandle {
+    inner: Arc<CoalescedInner>,
+}
+
+struct CoalescedInner {
+    handle: ClientHandle,
+    policy: CoalescePolicy,
+    /// Latest progress value recorded locally.
+    current: AtomicU64,
+    /// Last progress value offered to the store, which thresholds count from.
+    published: AtomicU64,
+    /// Last progress value the store is known to have queued; a bounded
+    /// store may drop published values that never reach it.
+    delivered: AtomicU64,
+    /// Nanoseconds since `origin` at which the last update was sent.
+    last_publish: AtomicU64,
+    origin: Instant,
+}
+
+impl CoalescedHandle {
+    fn new(handle: ClientHandle, policy: CoalescePolicy) -> Self {
+        Self {
+            inner: Arc::new(CoalescedInner {
+                handle,
+                policy,
+                current: AtomicU64::new(0),
+                published: AtomicU64::new(0),
+                delivered: AtomicU64::new(0),
+                last_publish: AtomicU64::new(0),
+                origin: Instant::now(),
+            }),
+        }
+    }
+
+    /// Return the wrapped handle.
+    pub fn handle(&self) -> &ClientHandle {
+        &self.inner.handle
+    }
+
+    /// Record the current completed units, publishing if a threshold is met.
+    pub fn set_current(&self, current: u64) -> Result<(), ReportError> {
+        self.inner.current.store(current, Ordering::Relaxed);
+        self.inner.maybe_publish(current)
+    }
+
+    /// Add `delta` completed units, publishing if a threshold is met.
+    pub fn inc(&self, delta: u64) -> Result<(), ReportError> {
+        let current = self
+            .inner
+            .current
+            .fetch_add(delta, Ordering::Relaxed)
+            .saturating_add(delta);
+        self.inner.maybe_publish(current)
+    }
+
+    /// Send the latest progress value immediately.
+    pub fn flush(&self) -> Result<(), ReportError> {
+        self.inner.flush()
+    }
+
+    /// Update the display label for this client/task.
+    pub fn set_label(&self, label: impl Into<String>) -> Result<(), ReportError> {
+        self.inner.handle.set_label(label)
+    }
+
+    /// Update the total units of work for this client/task.
+    pub fn set_total(&self, total: Option<u64>) -> Result<(), ReportError> {
+        self.inner.handle.set_total(total)
+    }
+
+    /// Send pending progress with the completed status.
+    pub fn complete(&self) -> Result<(), ReportError> {
+        self.inner.finish(TaskStatus::Completed)
+    }
+
+    /// Send pending progress with the failed status.
+    pub fn fail(&self) -> Result<(), ReportError> {
+        self.inner.finish(TaskStatus::Failed)
+    }
+
+    /// Send pending progress with the canceled status.
+    pub fn cancel(&self) -> Result<(), ReportError> {
+        self.inner.finish(TaskStatus::Canceled)
+    }
+}
+
+impl CoalescedInner {
+    /// Publish `current` if either policy threshold has been crossed.
+    ///
+    /// Only the thread that wins the compare-exchange on `last_publish`
+    /// sends, so concurrent callers never publish the same window twice.
+    fn maybe_publish(&self, current: u64) -> Result<(), ReportError> {
+        let now = self.now_nanos();
+        let last = self.last_publish.load(Ordering::Relaxed);
+        let published = self.published.load(Ordering::Relaxed);
+
+        let interval_due = match self.policy.interval {
+            Some(interval) => u128::from(now.saturating_sub(last)) >= interval.as_nanos(),
+            None => self.policy.steps.is_none(),
+        };
+        let steps_due = self
+            .policy
+            .steps
+            .is_some_and(|steps| current.abs_diff(published) >= steps);
+        if !interval_due && !steps_due {
+            return Ok(());
+        }
+
+        if self
+            .last_publish
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            self.published.store(current, Ordering::Relaxed);
+            if self.handle.try_set_current(current)? {
+                self.delivered.store(current, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+
+    /// Send the latest value if the store may not have it yet.
+    ///
+    /// Unlike threshold publishes, this update is never dropped, so the
+    /// store always ends up with the final count.
+    fn flush(&self) -> Result<(), ReportError> {
+        let current = self.current.load(Ordering::Relaxed);
+        if self.delivered.load(Ordering::Relaxed) == current {
+            return Ok(());
+        }
+        self.handle.set_current_reliably(current)?;
+        self.mark_delivered(current);
+        Ok(())
+    }
+
+    /// Send the latest value together with a final status.
+    fn finish(&self, status: TaskStatus) -> Result<(), ReportError> {
+        let current = self.current.load(Ordering::Relaxed);
+        self.handle.set_status_at(status, current)?;
+        self.mark_delivered(current);
+        Ok(())
+    }
+
+    fn mark_delivered(&self, current: u64) {
+        self.published.store(current, Ordering::Relaxed);
+        self.delivered.store(current, Ordering::Relaxed);
+        self.last_publish.store(self.now_nanos(), Ordering::Relaxed);
+    }
+
+    fn now_nanos(&self) -> u64 {
+        self.origin.elapsed().as_nanos().min(u128::from(u64::MAX)) as u64
+    }
+}
+
+impl Drop for CoalescedInner {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Client handle whose progress is a lock-free counter shared with the store.
+///
+/// `inc` and `set_current` are plain atomic operations and never send a
+/// message; label, total, and status changes still travel over the channel.
+/// Clones share the same counter.
+#[derive(Clone)]
+pub struct AtomicHandle {
+    handle: ClientHandle,
+    counter: Arc<AtomicU64>,
+}
+
+impl AtomicHandle {
+    /// Return the internal identifier for this client/task.
+    pub fn id(&self) -> TaskId {
+        self.handle.id
+    }
+
+    /// Add `delta` completed units.
+    pub fn inc(&self, delta: u64) {
+        self.counter.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    /// Set the current completed units of work.
+    pub fn set_current(&self, current: u64) {
+        self.counter.store(current, Ordering::Relaxed);
+    }
+
+    /// Return the current completed units of work.
+    pub fn current(&self) -> u64 {
+        self.counter.load(Ordering::Relaxed)
+    }
+
+    /// Update the display label for this client/task.
+    pub fn set_label(&self, label: impl Into<String>) -> Result<(), ReportError> {
+        self.handle.set_label(label)
+    }
+
+    /// Update the total units of work for this client/task.
+    pub fn set_total(&self, total: Option<u64>) -> Result<(), ReportError> {
+        self.handle.set_total(total)
+    }
+
+    /// Mark this client/task as completed.
+    pub fn complete(&self) -> Result<(), ReportError> {
+        self.handle.complete()
+    }
+
+    /// Mark this client/task as failed.
+    pub fn fail(&self) -> Result<(), ReportError> {
+        self.handle.fail()
+    }
+
+    /// Mark this client/task as canceled.
+    pub fn cancel(&self) -> Result<(), ReportError> {
+        self.handle.cancel()
+    }
+}
@@ -1,13 +1,14 @@
-/// ==============================================================================
-/// src/client_state.rs
-/// Core progress state shared across modules.
-///
-/// Each `ClientState` represents a "client" (what we previously called a task)
-/// whose progress we want to track. This type is part of the public API and is
-/// intended for client-side consumption (simulators, UIs, and exporters).
-/// ==============================================================================
+//! ==============================================================================
+//! src/client_state.rs
+//! Core progress state shared across modules.
+//!
+//! Each `ClientState` represents a "client" (what we previously called a task)
+//! whose progress we want to track. This type is part of the public API and is
+//! intended for client-side consumption (simulators, UIs, and exporters).
+//! ==============================================================================
 
 
+use std::fmt;
 use std::time::{Duration, Instant};
 
 /// Opaque unique identifier for a client.
@@ -19,17 +20,80 @@ impl TaskId {
     pub fn new() -> Self {
         Self(rand::random())
     }
+
+    /// Derive an id from a stable key such as a label or parameter string.
+    ///
+    /// The same key gives the same id in every run and process (64-bit
+    /// FNV-1a), so distinct tasks need distinct keys.
+    pub fn from_key(key: impl AsRef<[u8]>) -> Self {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+        let hash = key.as_ref().iter().fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        });
+        Self(hash)
+    }
+}
+
+impl Default for TaskId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How a reporter picks the `TaskId` of each client it starts.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdAllocation {
+    /// A random id per client; ids differ between runs.
+    #[default]
+    Random,
+    /// 0, 1, 2, ... in start order, counted per store.
+    Sequential,
+    /// `TaskId::from_key` of the client's label, so clients with the same
+    /// label share an id and are merged by the store.
+    Label,
 }
 
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskStatus {
+    /// Preparing to run, before the first iteration.
+    SettingUp,
     Running,
+    /// Held at a checkpoint until resumed from the runtime.
+    Paused,
+    /// Cleaning up after the last iteration.
+    Finalizing,
     Completed,
     Failed,
     Canceled,
 }
 
+impl TaskStatus {
+    /// Return whether the task has finished and will not change again.
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Canceled
+        )
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TaskStatus::SettingUp => "Setting up",
+            TaskStatus::Running => "Running",
+            TaskStatus::Paused => "Paused",
+            TaskStatus::Finalizing => "Finalizing",
+            TaskStatus::Completed => "Completed",
+            TaskStatus::Failed => "Failed",
+            TaskStatus::Canceled => "Canceled",
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ClientState {
     /// Opaque unique identifier for internal store bookkeeping; never displayed.
@@ -47,9 +111,61 @@ pub struct ClientState {
     /// Monotonic time of the most recent update to this task.
     pub last_update: Instant,
     /// Monotonic time of the most recent progress update (current tick).
+    ///
+    /// While the client is paused, this is when the pause began instead.
     pub last_progress_update: Instant,
     /// Duration of the most recently completed iteration.
     pub last_iter_duration: Option<Duration>,
+    /// OS thread id of the worker running this client, when known.
+    ///
+    /// Used by the runtime to attribute per-thread CPU usage to the client.
+    pub thread_id: Option<u64>,
+    /// Log messages attached to this client, oldest first.
+    ///
+    /// In an update these are appended to the stored messages; the store
+    /// only keeps the most recent ones.
+    pub messages: Vec<String>,
+    /// User-defined named values such as loss or energy, in first-seen order.
+    ///
+    /// In an update each entry replaces the stored value of the same name.
+    pub metrics: Vec<(String, f64)>,
+    /// Named parameters of the client, such as the point of a `Sweep`, in
+    /// first-seen order.
+    ///
+    /// In an update each entry replaces the stored value of the same name.
+    pub params: Vec<(String, f64)>,
+    /// Name of the current phase, when the client reports phases.
+    ///
+    /// In an update a name begins a new phase: the current one moves to
+    /// `phases` and `current` restarts from zero.
+    pub phase: Option<String>,
+    /// Number of phases the client expects to run, when known.
+    pub phase_count: Option<usize>,
+    /// Monotonic time the current phase began.
+    pub phase_start: Instant,
+    /// Time spent paused since the current phase began, or since the start
+    /// for clients without phases; rates leave it out. Excludes an ongoing
+    /// pause.
+    ///
+    /// Ignored in an update; the store adds each pause as it ends.
+    pub paused: Duration,
+    /// Phases that have ended, oldest first.
+    ///
+    /// Ignored in an update; the store records each phase as it ends.
+    pub phases: Vec<Phase>,
+}
+
+/// A phase of a client that has ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phase {
+    /// Name given to `ClientHandle::begin_phase`.
+    pub name: String,
+    /// Units completed in the phase.
+    pub completed: u64,
+    /// Units the phase expected, when known.
+    pub total: Option<u64>,
+    /// Time from the start of the phase to the start of the next one.
+    pub duration: Duration,
 }
 
 impl ClientState {
@@ -66,6 +182,15 @@ impl ClientState {
             last_update: now,
             last_progress_update: now,
             last_iter_duration: None,
+            thread_id: None,
+            messages: Vec::new(),
+            metrics: Vec::new(),
+            params: Vec::new(),
+            phase: None,
+            phase_count: None,
+            phase_start: now,
+            paused: Duration::ZERO,
+            phases: Vec::new(),
         }
     }
 
@@ -84,6 +209,98 @@ impl ClientState {
             last_update,
             last_progress_update: last_update,
             last_iter_duration: None,
+            thread_id: None,
+            messages: Vec::new(),
+            metrics: Vec::new(),
+            params: Vec::new(),
+            phase: None,
+            phase_count: None,
+            phase_start: last_update,
+            paused: Duration::ZERO,
+            phases: Vec::new(),
         }
     }
+
+    /// Return the units done and expected over the whole client, or `None`
+    /// if the total is unknown.
+    ///
+    /// Without phases, or with an unknown number of them, this is `current`
+    /// and `total`. Otherwise ended phases count as done at the size they
+    /// expected, or reached if that was unknown, and phases yet to run are
+    /// estimated at the mean size of the known ones: phase 2 of 4 at
+    /// 340/1000 after a phase of 500 is 840/3000.
+    pub fn overall_progress(&self) -> Option<(u64, u64)> {
+        let count = self.phase_count.filter(|count| *count > 0);
+        let (Some(number), Some(count)) = (self.phase_number(), count) else {
+            let total = self.total?;
+            return Some((self.current.unwrap_or(0).min(total), total));
+        };
+        let ended: u64 = self
+            .phases
+            .iter()
+            .map(|phase| phase.total.unwrap_or(phase.completed))
+            .sum();
+        let known = self.phases.len() as u64 + u64::from(self.total.is_some());
+        let estimate = (ended + self.total.unwrap_or(0)).checked_div(known)?;
+        let total = self.total.unwrap_or(estimate);
+        let current = self.current.unwrap_or(0).min(total);
+        let upcoming = count.saturating_sub(number) as u64;
+        Some((ended + current, ended + total + upcoming * estimate))
+    }
+
+    /// Return the 1-based number of the current phase, or `None` if the
+    /// client does not report phases.
+    pub fn phase_number(&self) -> Option<usize> {
+        self.phase.as_ref().map(|_| self.phases.len() + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ended(total: Option<u64>, completed: u64) -> Phase {
+        Phase {
+            name: "earlier".to_string(),
+            completed,
+            total,
+            duration: Duration::from_secs(1),
+        }
+    }
+
+    fn in_phase(phases: Vec<Phase>, count: usize, current: u64, total: Option<u64>) -> ClientState {
+        let mut state = ClientState::new("sim", total);
+        state.current = Some(current);
+        state.phase = Some("current".to_string());
+        state.phase_count = Some(count);
+        state.phases = phases;
+        state
+    }
+
+    #[test]
+    fn ended_phases_count_at_their_own_size() {
+        let state = in_phase(vec![ended(Some(500), 500)], 4, 340, Some(1_000));
+        assert_eq!(state.overall_progress(), Some((840, 3_000)));
+
+        let phases = vec![ended(None, 200), ended(Some(400), 100)];
+        let state = in_phase(phases, 3, 50, Some(300));
+        assert_eq!(state.overall_progress(), Some((650, 900)));
+    }
+
+    #[test]
+    fn an_unknown_current_total_is_estimated_from_ended_phases() {
+        let state = in_phase(vec![ended(Some(500), 500)], 3, 200, None);
+        assert_eq!(state.overall_progress(), Some((700, 1_500)));
+
+        let state = in_phase(Vec::new(), 3, 200, None);
+        assert_eq!(state.overall_progress(), None);
+    }
+
+    #[test]
+    fn clients_without_a_phase_count_report_the_current_phase() {
+        let mut state = in_phase(vec![ended(Some(500), 500)], 0, 340, Some(1_000));
+        assert_eq!(state.overall_progress(), Some((340, 1_000)));
+        state.phase_count = None;
+        assert_eq!(state.overall_progress(), Some((340, 1_000)));
+    }
 }
@@ -1,12 +1,41 @@
-/// ==============================================================================
-/// src/client_store.rs
-/// In-memory store for the latest client states.
-/// ==============================================================================
+//! ==============================================================================
+//! src/client_store.rs
+//! In-memory store for the latest client states.
+//! ==============================================================================
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::{ClientReporter, ClientState, TaskId};
+use crate::client_control::ClientControl;
+use crate::run_report::IterationSamples;
+use crate::{Backpressure, ClientReporter, ClientState, Phase, TaskId, TaskStatus};
+
+/// Maximum number of log messages kept per client.
+const MAX_CLIENT_MESSAGES: usize = 100;
+
+/// Maximum number of messages kept in the global log buffer.
+const MAX_GLOBAL_LOGS: usize = 1_000;
+
+/// Message carried from reporters to the store.
+// State updates make up nearly all traffic, so boxing them would only add an
+// allocation per update.
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum StoreMessage {
+    /// A full or partial client state update.
+    State(ClientState),
+    /// Register a shared progress counter for an existing client.
+    Counter { id: TaskId, counter: Arc<AtomicU64> },
+    /// Register the pause control of a newly started client.
+    Control {
+        id: TaskId,
+        control: Arc<ClientControl>,
+    },
+    /// A message for the global log buffer.
+    Log(String),
+}
 
 /// In-memory store of the latest client states keyed by `TaskId`.
 ///
@@ -14,8 +43,20 @@ use crate::{ClientReporter, ClientState, TaskId};
 /// a `ClientReporter` running in simulation threads.
 #[derive(Debug)]
 pub struct ClientStore {
-    rx: Receiver<ClientState>,
+    rx: Receiver<StoreMessage>,
     clients: HashMap<TaskId, ClientState>,
+    /// Shared progress counters registered by `AtomicHandle`s.
+    counters: HashMap<TaskId, Arc<AtomicU64>>,
+    /// Pause and cancellation controls of clients started through a `ClientHandle`.
+    controls: HashMap<TaskId, Arc<ClientControl>>,
+    /// Iteration durations observed for each client, for the run report.
+    iterations: HashMap<TaskId, IterationSamples>,
+    /// Messages not tied to a client, oldest first.
+    logs: VecDeque<String>,
+    /// Messages received since the store was created, including evicted ones.
+    log_count: u64,
+    /// Updates discarded by a bounded reporter; shared with the reporter.
+    dropped: Arc<AtomicU64>,
 }
 
 impl ClientStore {
@@ -26,56 +67,381 @@ impl ClientStore {
         let store = Self {
             rx,
             clients: HashMap::new(),
+            counters: HashMap::new(),
+            controls: HashMap::new(),
+            iterations: HashMap::new(),
+            logs: VecDeque::new(),
+            log_count: 0,
+            dropped: Arc::new(AtomicU64::new(0)),
+        };
+        (reporter, store)
+    }
+
+    /// Create a store fed by a bounded channel of `capacity` updates.
+    ///
+    /// When the channel is full, reporters follow `policy`: either block
+    /// until the store drains, or drop progress-only updates while still
+    /// delivering label, total, and status changes.
+    pub fn bounded(capacity: usize, policy: Backpressure) -> (ClientReporter, Self) {
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let reporter = ClientReporter::bounded(tx, policy, Arc::clone(&dropped));
+        let store = Self {
+            rx,
+            clients: HashMap::new(),
+            counters: HashMap::new(),
+            controls: HashMap::new(),
+            iterations: HashMap::new(),
+            logs: VecDeque::new(),
+            log_count: 0,
+            dropped,
         };
         (reporter, store)
     }
 
+    /// Return the global log buffer, oldest message first.
+    ///
+    /// Only the most recent messages are kept.
+    pub fn logs(&self) -> impl ExactSizeIterator<Item = &str> + DoubleEndedIterator {
+        self.logs.iter().map(String::as_str)
+    }
+
+    /// Return how many global messages were received in total, including
+    /// those no longer kept in `logs`.
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub(crate) fn log_count(&self) -> u64 {
+        self.log_count
+    }
+
+    /// Return how many updates were dropped because the channel was full.
+    pub fn dropped_updates(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Pause a client at its next `ClientHandle::checkpoint`.
+    ///
+    /// Returns `false` if the client has no pause control (it is unknown, or
+    /// was not started through a `ClientHandle`).
+    pub fn pause(&self, id: TaskId) -> bool {
+        self.set_paused(id, true)
+    }
+
+    /// Resume a paused client.
+    ///
+    /// Returns `false` if the client has no pause control.
+    pub fn resume(&self, id: TaskId) -> bool {
+        self.set_paused(id, false)
+    }
+
+    /// Pause every client at its next checkpoint.
+    pub fn pause_all(&self) {
+        for control in self.controls.values() {
+            control.set_paused(true);
+        }
+    }
+
+    /// Resume every paused client.
+    pub fn resume_all(&self) {
+        for control in self.controls.values() {
+            control.set_paused(false);
+        }
+    }
+
+    /// Ask a client to stop at its next checkpoint.
+    ///
+    /// Other clients keep running. Returns `false` if the client has no
+    /// control.
+    pub fn cancel(&self, id: TaskId) -> bool {
+        match self.controls.get(&id) {
+            Some(control) => {
+                control.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Ask every client to stop at its next checkpoint.
+    pub fn cancel_all(&self) {
+        for control in self.controls.values() {
+            control.cancel();
+        }
+    }
+
+    fn set_paused(&self, id: TaskId, paused: bool) -> bool {
+        match self.controls.get(&id) {
+            Some(control) => {
+                control.set_paused(paused);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Drain all pending updates and merge them into the store.
     ///
     /// This is non-blocking and processes all currently queued updates.
+    /// Registered atomic counters are read afterwards and merged as progress
+    /// updates, so iteration durations are derived from counter deltas.
     pub fn drain(&mut self) {
-        for state in self.rx.try_iter() {
-            self.clients
-                .entry(state.id)
-                .and_modify(|existing| merge_state(existing, &state))
-                .or_insert(state);
+        for message in self.rx.try_iter() {
+            match message {
+                StoreMessage::State(state) => match self.clients.get_mut(&state.id) {
+                    Some(existing) => {
+                        if let Some((elapsed, steps)) = merge_state(existing, &state) {
+                            record_iterations(&mut self.iterations, state.id, elapsed, steps);
+                        }
+                    }
+                    None => {
+                        self.clients.insert(state.id, state);
+                    }
+                },
+                StoreMessage::Counter { id, counter } => {
+                    self.counters.insert(id, counter);
+                }
+                StoreMessage::Control { id, control } => {
+                    self.controls.insert(id, control);
+                }
+                StoreMessage::Log(message) => {
+                    push_log(&mut self.logs, message);
+                    self.log_count += 1;
+                }
+            }
+        }
+
+        let now = Instant::now();
+        for (id, counter) in &self.counters {
+            let current = counter.load(Ordering::Relaxed);
+            if let Some(existing) = self.clients.get_mut(id) {
+                if existing.current != Some(current) {
+                    let mut update = ClientState::partial(*id, existing.start_time, now);
+                    update.current = Some(current);
+                    if let Some((elapsed, steps)) = merge_state(existing, &update) {
+                        record_iterations(&mut self.iterations, *id, elapsed, steps);
+                    }
+                }
+            }
         }
     }
 
+    /// Return the iteration durations observed for a client.
+    pub(crate) fn iteration_samples(&self, id: TaskId) -> Option<&IterationSamples> {
+        self.iterations.get(&id)
+    }
+
     /// Return a snapshot of the latest known state for all clients.
     ///
     /// The snapshot is a cloned vector to keep read access independent
     /// from the store's internal mutation.
     pub fn snapshot(&self) -> Vec<ClientState> {
-        self.clients.values().cloned().collect()
+        self.clients
+            .values()
+            .cloned()
+            .map(|mut state| {
+                if let Some(counter) = self.counters.get(&state.id) {
+                    state.current = Some(counter.load(Ordering::Relaxed));
+                }
+                state
+            })
+            .collect()
+    }
+}
+
+impl Drop for ClientStore {
+    /// Release paused workers; nothing could resume them once the store is gone.
+    fn drop(&mut self) {
+        self.resume_all();
     }
 }
 
+/// Append to a capped log buffer, evicting the oldest message when full.
+fn push_log(logs: &mut VecDeque<String>, message: String) {
+    if logs.len() == MAX_GLOBAL_LOGS {
+        logs.pop_front();
+    }
+    logs.push_back(message);
+}
+
 /// Merge a partial update into the existing stored state.
 ///
-/// Fields that are `None` in the update are left unchanged.
-fn merge_state(existing: &mut ClientState, update: &ClientState) {
+/// Fields that are `None` in the update are left unchanged. Returns the time
+/// taken and the number of iterations when the update advanced progress.
+fn merge_state(existing: &mut ClientState, update: &ClientState) -> Option<(Duration, u64)> {
+    let mut advanced = None;
     if let Some(label) = &update.label {
         existing.label = Some(label.clone());
     }
     if let Some(status) = update.status {
+        match existing.status {
+            // Setup is not part of the first iteration.
+            Some(TaskStatus::SettingUp) => existing.last_progress_update = update.last_update,
+            // Neither is a pause, which began at `last_progress_update`.
+            Some(TaskStatus::Paused) if status != TaskStatus::Paused => {
+                existing.paused += update
+                    .last_update
+                    .saturating_duration_since(existing.last_progress_update);
+                existing.last_progress_update = update.last_update;
+            }
+            Some(TaskStatus::Paused) => {}
+            _ if status == TaskStatus::Paused => {
+                existing.last_progress_update = update.last_update;
+            }
+            _ => {}
+        }
         existing.status = Some(status);
     }
+    if let Some(name) = &update.phase {
+        if let Some(previous) = existing.phase.replace(name.clone()) {
+            existing.phases.push(Phase {
+                name: previous,
+                completed: existing.current.unwrap_or(0),
+                total: existing.total,
+                duration: update.last_update.duration_since(existing.phase_start),
+            });
+        }
+        // The new phase counts from zero, so the reset is not progress.
+        existing.phase_start = update.last_update;
+        existing.paused = Duration::ZERO;
+        existing.total = None;
+        existing.current = Some(0);
+        existing.last_progress_update = update.last_update;
+        existing.last_iter_duration = None;
+    }
+    if update.phase_count.is_some() {
+        existing.phase_count = update.phase_count;
+    }
     if update.total.is_some() {
         existing.total = update.total;
     }
     if let Some(new_current) = update.current {
         let prev_current = existing.current;
         if prev_current != Some(new_current) {
-            existing.last_iter_duration = Some(
-                update
-                    .last_update
-                    .duration_since(existing.last_progress_update),
-            );
+            // Coalesced updates may cover several steps at once, so spread
+            // the elapsed time over the number of steps advanced.
+            let steps = new_current.abs_diff(prev_current.unwrap_or(0)).max(1);
+            let elapsed = update
+                .last_update
+                .duration_since(existing.last_progress_update);
+            existing.last_iter_duration =
+                Some(elapsed / u32::try_from(steps).unwrap_or(u32::MAX));
             existing.last_progress_update = update.last_update;
+            if new_current > prev_current.unwrap_or(0) {
+                advanced = Some((elapsed, steps));
+            }
         }
         existing.current = Some(new_current);
     }
+    if update.thread_id.is_some() {
+        existing.thread_id = update.thread_id;
+    }
+    if !update.messages.is_empty() {
+        existing.messages.extend(update.messages.iter().cloned());
+        let excess = existing.messages.len().saturating_sub(MAX_CLIENT_MESSAGES);
+        existing.messages.drain(..excess);
+    }
+    merge_named(&mut existing.metrics, &update.metrics);
+    merge_named(&mut existing.params, &update.params);
     existing.start_time = update.start_time;
     existing.last_update = update.last_update;
+    advanced
+}
+
+/// Replace the values of `updates` in `values` by name, appending new names.
+fn merge_named(values: &mut Vec<(String, f64)>, updates: &[(String, f64)]) {
+    for (name, value) in updates {
+        match values.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = *value,
+            None => values.push((name.clone(), *value)),
+        }
+    }
+}
+
+/// Add `steps` iterations that took `elapsed` to the samples of client `id`.
+fn record_iterations(
+    iterations: &mut HashMap<TaskId, IterationSamples>,
+    id: TaskId,
+    elapsed: Duration,
+    steps: u64,
+) {
+    iterations.entry(id).or_default().record(elapsed, steps);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty update to `state`, sent `millis` after its start.
+    fn update_at(state: &ClientState, millis: u64) -> ClientState {
+        let at = state.start_time + Duration::from_millis(millis);
+        ClientState::partial(state.id, state.start_time, at)
+    }
+
+    fn status_at(state: &ClientState, millis: u64, status: TaskStatus) -> ClientState {
+        let mut update = update_at(state, millis);
+        update.status = Some(status);
+        update
+    }
+
+    fn current_at(state: &ClientState, millis: u64, current: u64) -> ClientState {
+        let mut update = update_at(state, millis);
+        update.current = Some(current);
+        update
+    }
+
+    #[test]
+    fn pauses_are_not_iteration_time() {
+        let mut state = ClientState::new("sim", Some(10));
+        let updates = [
+            current_at(&state, 100, 1),
+            status_at(&state, 150, TaskStatus::Paused),
+            status_at(&state, 5_150, TaskStatus::Running),
+        ];
+        let next = current_at(&state, 5_250, 2);
+        for update in &updates {
+            merge_state(&mut state, update);
+        }
+        let advanced = merge_state(&mut state, &next);
+
+        assert_eq!(advanced, Some((Duration::from_millis(100), 1)));
+        assert_eq!(state.last_iter_duration, Some(Duration::from_millis(100)));
+        assert_eq!(state.paused, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn a_new_phase_forgets_earlier_pauses() {
+        let mut state = ClientState::new("sim", None);
+        let mut phase = update_at(&state, 400);
+        phase.phase = Some("production".to_string());
+        let updates = [
+            status_at(&state, 100, TaskStatus::Paused),
+            status_at(&state, 300, TaskStatus::Running),
+            phase,
+        ];
+        for update in &updates {
+            merge_state(&mut state, update);
+        }
+
+        assert_eq!(state.paused, Duration::ZERO);
+    }
+
+    #[test]
+    fn set_progress_is_not_dropped_by_a_full_store() {
+        // Starting a client queues its state and its control flags.
+        let (reporter, mut store) = ClientStore::bounded(2, Backpressure::DropProgress);
+        let client = reporter.start("sim", None).expect("store is open");
+        client.set_current(1).expect("store is open");
+        assert_eq!(store.dropped_updates(), 1);
+
+        let sender = std::thread::spawn(move || client.set_progress(2, None));
+        // Give the update time to meet the full store before draining it.
+        std::thread::sleep(Duration::from_millis(50));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while store.snapshot().first().and_then(|state| state.current) != Some(2) {
+            assert!(Instant::now() < deadline, "set_progress never arrived");
+            store.drain();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        sender.join().unwrap().expect("store is open");
+        assert_eq!(store.dropped_updates(), 1);
+    }
 }
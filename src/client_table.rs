@@ -0,0 +1,399 @@
+//! ==============================================================================
+//! src/client_table.rs
+//! Clients table for the runtime TUI: one row per client, aligned columns.
+//! ==============================================================================
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    prelude::*,
+    widgets::{Cell, HighlightSpacing, Row, Table, TableState},
+};
+
+use crate::gauge;
+use crate::layout::{ClientColumns, Column};
+use crate::runtime::format_duration;
+use crate::theme::Theme;
+use crate::{ClientState, TaskStatus};
+
+/// Widest label column; longer labels are truncated with `…`.
+const MAX_LABEL_WIDTH: usize = 32;
+
+/// Narrowest automatic label column.
+const MIN_LABEL_WIDTH: usize = 8;
+
+/// Widest message column; longer messages are truncated with `…`.
+const MAX_MESSAGE_WIDTH: usize = 40;
+
+/// Narrowest bar worth drawing.
+const MIN_BAR_WIDTH: u16 = 10;
+
+/// Cells between columns.
+const COLUMN_SPACING: u16 = 1;
+
+/// Fill for the unfilled part of a progress bar.
+const BAR_TRACK: &str = "░";
+
+/// Everything the clients table needs to draw one frame.
+pub(crate) struct ClientsView<'a> {
+    pub(crate) snapshot: &'a [ClientState],
+    pub(crate) columns: &'a ClientColumns,
+    /// Per-thread CPU usage, when resources are monitored.
+    pub(crate) thread_cpu: Option<&'a HashMap<u64, f64>>,
+    /// Row to highlight, if any.
+    pub(crate) selected: Option<usize>,
+    /// Parameter the rows are sorted by, shown in the title.
+    pub(crate) sort_by: Option<&'a str>,
+    pub(crate) theme: &'a Theme,
+    /// Time since the session started, driving the throbber.
+    pub(crate) elapsed: Duration,
+}
+
+/// A visible column after expanding `Column::Metrics` into one column per
+/// metric name.
+struct TableColumn<'a> {
+    column: Column,
+    /// Metric name for metric columns.
+    metric: Option<&'a str>,
+    width: Option<Constraint>,
+}
+
+impl TableColumn<'_> {
+    fn title(&self) -> &str {
+        self.metric.unwrap_or(self.column.title())
+    }
+
+    /// Constraint used when no explicit width was configured.
+    ///
+    /// Labels never take more than a third of the table, so narrow terminals
+    /// keep room for progress.
+    fn auto_width(&self, content: usize, available: u16) -> Constraint {
+        match self.column {
+            Column::Bar => Constraint::Fill(1),
+            Column::Label => {
+                let cap = MAX_LABEL_WIDTH.min(usize::from(available / 3).max(MIN_LABEL_WIDTH));
+                Constraint::Length(content.min(cap) as u16)
+            }
+            Column::Message => Constraint::Length(content.min(MAX_MESSAGE_WIDTH) as u16),
+            _ => Constraint::Length(content as u16),
+        }
+    }
+}
+
+/// Render the clients table into `area` (borders included).
+pub(crate) fn render_clients(frame: &mut Frame<'_>, area: Rect, view: &ClientsView<'_>) {
+    let theme = view.theme;
+    let title = match view.sort_by {
+        Some(name) => format!("Clients (by {name})"),
+        None => "Clients".to_string(),
+    };
+    let block = theme.block(&title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let marker = if view.selected.is_some() { "> " } else { "" };
+    let available = inner.width.saturating_sub(marker.len() as u16);
+    let now = Instant::now();
+
+    let mut columns = table_columns(view);
+    // Text for every cell except bars, which depend on the final width.
+    let mut rows: Vec<Vec<String>> = view
+        .snapshot
+        .iter()
+        .map(|state| {
+            columns
+                .iter()
+                .map(|column| cell_text(state, column, view.thread_cpu, now))
+                .collect()
+        })
+        .collect();
+    let mut constraints = resolve_widths(&columns, &rows, available);
+
+    // Drop the lowest-ranked optional column until the minimum widths fit.
+    while min_width(&constraints, available) > available {
+        let lowest = columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, column)| column.column.drop_rank().map(|rank| (rank, i)))
+            .min_by_key(|(rank, i)| (*rank, std::cmp::Reverse(*i)));
+        let Some((_, i)) = lowest else {
+            break;
+        };
+        columns.remove(i);
+        constraints.remove(i);
+        for row in &mut rows {
+            row.remove(i);
+        }
+    }
+
+    let widths: Vec<u16> = Layout::horizontal(constraints.iter().copied())
+        .spacing(COLUMN_SPACING)
+        .flex(layout::Flex::Start)
+        .split(Rect::new(0, 0, available, 1))
+        .iter()
+        .map(|rect| rect.width)
+        .collect();
+
+    let header = Row::new(
+        columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| {
+                align(column, gauge::fit(column.title(), usize::from(*width)))
+            }),
+    )
+    .style(theme.header);
+
+    let body = view.snapshot.iter().zip(rows).map(|(state, texts)| {
+        let cells = columns
+            .iter()
+            .zip(&widths)
+            .zip(texts)
+            .map(|((column, width), text)| {
+                let width = usize::from(*width);
+                match column.column {
+                    Column::Bar => {
+                        Cell::from(Line::from(bar_spans(state, theme, width, view.elapsed)))
+                    }
+                    Column::Label => Cell::from(gauge::fit(&text, width)).style(theme.label),
+                    Column::Status => Cell::from(text).style(theme.status(state.status)),
+                    Column::Message => Cell::from(gauge::fit(&text, width)).style(theme.dim),
+                    _ => align(column, format!("{text:>width$}")),
+                }
+            });
+        Row::new(cells)
+    });
+
+    let table = Table::new(body, widths.iter().map(|width| Constraint::Length(*width)))
+        .header(header)
+        .column_spacing(COLUMN_SPACING)
+        .highlight_symbol(marker)
+        .highlight_spacing(HighlightSpacing::WhenSelected)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default().with_selected(view.selected);
+    frame.render_stateful_widget(table, inner, &mut state);
+}
+
+/// Expand the configured columns, skipping those with nothing to show.
+fn table_columns<'a>(view: &ClientsView<'a>) -> Vec<TableColumn<'a>> {
+    let mut metric_names: Vec<&str> = Vec::new();
+    for (name, _) in view.snapshot.iter().flat_map(|state| &state.metrics) {
+        if !metric_names.contains(&name.as_str()) {
+            metric_names.push(name);
+        }
+    }
+
+    let mut columns = Vec::new();
+    for (column, width) in view.columns.visible() {
+        match column {
+            Column::Metrics => columns.extend(metric_names.iter().map(|name| TableColumn {
+                column,
+                metric: Some(name),
+                width,
+            })),
+            Column::Cpu if view.thread_cpu.is_none() => {}
+            Column::Message
+                if view.snapshot.iter().all(|state| state.messages.is_empty()) => {}
+            _ => columns.push(TableColumn {
+                column,
+                metric: None,
+                width,
+            }),
+        }
+    }
+    columns
+}
+
+/// Return a width constraint per column: the configured one, or one fitted
+/// to the header and cell contents.
+fn resolve_widths(
+    columns: &[TableColumn<'_>],
+    rows: &[Vec<String>],
+    available: u16,
+) -> Vec<Constraint> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            column.width.unwrap_or_else(|| {
+                let content = rows
+                    .iter()
+                    .map(|row| gauge::display_width(&row[i]))
+                    .chain([gauge::display_width(column.title())])
+                    .max()
+                    .unwrap_or(0);
+                column.auto_width(content, available)
+            })
+        })
+        .collect()
+}
+
+/// Return the smallest total width the constraints can be squeezed into.
+fn min_width(constraints: &[Constraint], available: u16) -> u16 {
+    let cells: u16 = constraints
+        .iter()
+        .map(|constraint| match *constraint {
+            Constraint::Length(n) | Constraint::Min(n) => n,
+            Constraint::Percentage(p) => {
+                u16::try_from(u32::from(available) * u32::from(p) / 100).unwrap_or(u16::MAX)
+            }
+            Constraint::Ratio(num, den) => (u64::from(available) * u64::from(num))
+                .checked_div(u64::from(den))
+                .map_or(0, |cells| u16::try_from(cells).unwrap_or(u16::MAX)),
+            Constraint::Fill(_) => MIN_BAR_WIDTH,
+            Constraint::Max(_) => 0,
+        })
+        .fold(0, u16::saturating_add);
+    let gaps = COLUMN_SPACING * (constraints.len() as u16).saturating_sub(1);
+    cells.saturating_add(gaps)
+}
+
+/// Right-align numeric columns; everything else stays left-aligned.
+fn align<'a>(column: &TableColumn<'_>, text: String) -> Cell<'a> {
+    match column.column {
+        Column::Label | Column::Status | Column::Bar | Column::Message => Cell::from(text),
+        _ => Cell::from(Line::from(text).right_aligned()),
+    }
+}
+
+/// Return the text shown in a non-bar cell.
+fn cell_text(
+    state: &ClientState,
+    column: &TableColumn<'_>,
+    thread_cpu: Option<&HashMap<u64, f64>>,
+    now: Instant,
+) -> String {
+    let current = state.current.unwrap_or(0);
+    let finished = state.status.is_some_and(|status| status.is_terminal());
+    let elapsed = if finished {
+        state.last_update.duration_since(state.start_time)
+    } else {
+        now.duration_since(state.start_time)
+    };
+    // Rate and ETA follow the current phase, which started counting afresh,
+    // and leave out time spent paused.
+    let paused = match state.status {
+        Some(TaskStatus::Paused) => {
+            state.paused + now.saturating_duration_since(state.last_progress_update)
+        }
+        _ => state.paused,
+    };
+    let phase_elapsed = match state.phase {
+        Some(_) if finished => state.last_update.duration_since(state.phase_start),
+        Some(_) => now.duration_since(state.phase_start),
+        None => elapsed,
+    }
+    .saturating_sub(paused);
+    let rate = (phase_elapsed.as_secs_f64() > 0.0)
+        .then(|| current as f64 / phase_elapsed.as_secs_f64());
+
+    match column.column {
+        Column::Label => state.label.clone().unwrap_or_else(|| "unnamed".to_string()),
+        Column::Status => state
+            .status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "Unknown".to_string()),
+        Column::Count => {
+            let total = state
+                .total
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "?".to_string());
+            match (state.phase.as_deref(), state.phase_number()) {
+                (Some(name), Some(number)) => {
+                    let count = state
+                        .phase_count
+                        .map(|count| format!("/{count}"))
+                        .unwrap_or_default();
+                    format!("phase {number}{count}: {name} {current}/{total}")
+                }
+                _ => format!("{current}/{total}"),
+            }
+        }
+        Column::Percent => match state.overall_progress() {
+            Some((done, total)) => format!("{}%", percent(done, total)),
+            None => "??%".to_string(),
+        },
+        Column::Bar => String::new(),
+        Column::Rate => rate.map(format_rate).unwrap_or_else(|| "--".to_string()),
+        Column::Eta => match (state.total, rate) {
+            (Some(total), Some(rate))
+                if !finished && state.status != Some(TaskStatus::Paused) && rate > 0.0 =>
+            {
+                let remaining = total.saturating_sub(current) as f64 / rate;
+                format_duration(Duration::from_secs_f64(remaining))
+            }
+            _ => "--".to_string(),
+        },
+        Column::Elapsed => format_duration(elapsed),
+        Column::LastIter => state
+            .last_iter_duration
+            .map(format_duration)
+            .unwrap_or_else(|| "--".to_string()),
+        Column::Cpu => state
+            .thread_id
+            .and_then(|tid| thread_cpu?.get(&tid))
+            .map(|cpu| format!("{cpu:.0}%"))
+            .unwrap_or_default(),
+        Column::Metrics => state
+            .metrics
+            .iter()
+            .find(|(name, _)| Some(name.as_str()) == column.metric)
+            .map(|(_, value)| format_metric(*value))
+            .unwrap_or_default(),
+        Column::Message => state.messages.last().cloned().unwrap_or_default(),
+    }
+}
+
+/// Render a progress bar `width` cells wide, or a throbber when the total
+/// is unknown.
+fn bar_spans<'a>(
+    state: &ClientState,
+    theme: &Theme,
+    width: usize,
+    elapsed: Duration,
+) -> Vec<Span<'a>> {
+    let track = |cells: usize| Span::styled(BAR_TRACK.repeat(cells), theme.dim);
+    match state.overall_progress() {
+        Some((done, total)) => {
+            let ratio = done as f64 / total.max(1) as f64;
+            let (filled, empty) = gauge::bar(ratio, width);
+            vec![Span::styled(filled, theme.bar), track(empty)]
+        }
+        None => {
+            let (before, block, after) = gauge::throbber(width, elapsed);
+            vec![track(before), Span::styled(block, theme.bar), track(after)]
+        }
+    }
+}
+
+/// Whole percent complete, never rounding up to 100 early.
+fn percent(current: u64, total: u64) -> u64 {
+    current
+        .min(total)
+        .saturating_mul(100)
+        .checked_div(total)
+        .unwrap_or(0)
+}
+
+/// Format an iteration rate with a unit that keeps it readable.
+fn format_rate(per_sec: f64) -> String {
+    if per_sec >= 1.0 {
+        format!("{per_sec:.1}/s")
+    } else if per_sec * 60.0 >= 1.0 {
+        format!("{:.1}/min", per_sec * 60.0)
+    } else {
+        format!("{:.1}/h", per_sec * 3_600.0)
+    }
+}
+
+/// Format a metric value compactly, switching to scientific notation for
+/// very large or small magnitudes.
+pub(crate) fn format_metric(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-3..1e5).contains(&magnitude) {
+        format!("{value:.3e}")
+    } else {
+        format!("{value:.4}")
+    }
+}
@@ -0,0 +1,87 @@
+//! ==============================================================================
+//! src/gauge.rs
+//! Text progress bars and display-width helpers for the runtime TUI.
+//! ==============================================================================
+
+use std::time::Duration;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Left-aligned blocks from one to seven eighths of a cell wide.
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Cells covered by the moving part of the throbber.
+const THROBBER_WIDTH: usize = 3;
+
+/// Time the throbber takes to move one cell.
+const THROBBER_STEP: Duration = Duration::from_millis(80);
+
+/// Render the filled part of a bar `width` cells wide at `ratio` (0..=1),
+/// with eighth-block precision.
+///
+/// Returns the filled cells (including a partial cell) and the number of
+/// empty cells left over, so callers can style them separately.
+pub(crate) fn bar(ratio: f64, width: usize) -> (String, usize) {
+    let eighths = (ratio.clamp(0.0, 1.0) * (width * 8) as f64).round() as usize;
+    let (full, partial) = (eighths / 8, eighths % 8);
+    let mut filled = "█".repeat(full);
+    let mut used = full;
+    if partial > 0 {
+        filled.push(EIGHTHS[partial - 1]);
+        used += 1;
+    }
+    (filled, width - used)
+}
+
+/// Render an indeterminate bar `width` cells wide: a short block bouncing
+/// between the ends, positioned by `elapsed`.
+///
+/// Returns empty cells before the block, the block, and empty cells after.
+pub(crate) fn throbber(width: usize, elapsed: Duration) -> (usize, String, usize) {
+    let block = THROBBER_WIDTH.min(width);
+    let travel = width - block;
+    let step = (elapsed.as_millis() / THROBBER_STEP.as_millis()) as usize;
+    let offset = match travel {
+        0 => 0,
+        _ => {
+            let phase = step % (2 * travel);
+            if phase <= travel {
+                phase
+            } else {
+                2 * travel - phase
+            }
+        }
+    };
+    (offset, "█".repeat(block), travel - offset)
+}
+
+/// Return the number of terminal cells `text` occupies.
+pub(crate) fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Truncate `text` to at most `width` cells, marking a cut with `…`, then
+/// pad it with spaces to exactly `width` cells.
+///
+/// Wide characters are never split, so CJK labels line up with ASCII ones.
+pub(crate) fn fit(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(width);
+    let mut used = 0;
+    if text.width() <= width {
+        out.push_str(text);
+        used = text.width();
+    } else if width > 0 {
+        for c in text.chars() {
+            let w = c.width().unwrap_or(0);
+            if used + w > width - 1 {
+                break;
+            }
+            out.push(c);
+            used += w;
+        }
+        out.push('…');
+        used += 1;
+    }
+    out.extend(std::iter::repeat_n(' ', width - used));
+    out
+}
@@ -0,0 +1,509 @@
+//! ==============================================================================
+//! src/key_map.rs
+//! Rebindable key bindings for the runtime TUI.
+//! ==============================================================================
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A user-facing action triggered by a key press in the TUI.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Begin quit confirmation.
+    Quit,
+    /// Confirm the open prompt (quit or cancel a client).
+    Confirm,
+    /// Dismiss the open prompt.
+    Dismiss,
+    /// Scroll the output pane up.
+    ScrollUp,
+    /// Scroll the output pane down.
+    ScrollDown,
+    /// Jump to the newest output.
+    ScrollBottom,
+    /// Select the previous client.
+    SelectPrev,
+    /// Select the next client.
+    SelectNext,
+    /// Pause the selected client at its next checkpoint.
+    Pause,
+    /// Resume the selected client.
+    Resume,
+    /// Pause every client.
+    PauseAll,
+    /// Resume every client.
+    ResumeAll,
+    /// Cancel the selected client after confirmation.
+    Cancel,
+    /// Show or hide the summary panel.
+    ToggleSummary,
+    /// Show or hide the selected client's detail panel.
+    ToggleDetail,
+    /// Show or hide the resources panel, starting sampling if needed.
+    ToggleResources,
+    /// Show or hide the captured output pane.
+    ToggleOutput,
+    /// Show or hide the logs pane.
+    ToggleLogs,
+    /// Sort clients by the next parameter, then back to start order.
+    CycleSort,
+    /// Toggle the help overlay.
+    Help,
+}
+
+impl Action {
+    /// Every action, in the order shown by the help overlay.
+    pub const ALL: &'static [Action] = &[
+        Action::Quit,
+        Action::Confirm,
+        Action::Dismiss,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::ScrollBottom,
+        Action::SelectPrev,
+        Action::SelectNext,
+        Action::Pause,
+        Action::Resume,
+        Action::PauseAll,
+        Action::ResumeAll,
+        Action::Cancel,
+        Action::ToggleSummary,
+        Action::ToggleDetail,
+        Action::ToggleResources,
+        Action::ToggleOutput,
+        Action::ToggleLogs,
+        Action::CycleSort,
+        Action::Help,
+    ];
+
+    /// Name used for this action in key map files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Confirm => "confirm",
+            Action::Dismiss => "dismiss",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollBottom => "scroll_bottom",
+            Action::SelectPrev => "select_prev",
+            Action::SelectNext => "select_next",
+            Action::Pause => "pause",
+            Action::Resume => "resume",
+            Action::PauseAll => "pause_all",
+            Action::ResumeAll => "resume_all",
+            Action::Cancel => "cancel",
+            Action::ToggleSummary => "toggle_summary",
+            Action::ToggleDetail => "toggle_detail",
+            Action::ToggleResources => "toggle_resources",
+            Action::ToggleOutput => "toggle_output",
+            Action::ToggleLogs => "toggle_logs",
+            Action::CycleSort => "cycle_sort",
+            Action::Help => "help",
+        }
+    }
+
+    /// Short description shown in the help overlay.
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "quit (asks for confirmation)",
+            Action::Confirm => "answer yes to a prompt",
+            Action::Dismiss => "answer no to a prompt",
+            Action::ScrollUp => "scroll output up",
+            Action::ScrollDown => "scroll output down",
+            Action::ScrollBottom => "jump to newest output",
+            Action::SelectPrev => "select previous client",
+            Action::SelectNext => "select next client",
+            Action::Pause => "pause selected client",
+            Action::Resume => "resume selected client",
+            Action::PauseAll => "pause all clients",
+            Action::ResumeAll => "resume all clients",
+            Action::Cancel => "cancel selected client (asks first)",
+            Action::ToggleSummary => "show/hide summary",
+            Action::ToggleDetail => "show/hide client detail",
+            Action::ToggleResources => "show/hide resources",
+            Action::ToggleOutput => "show/hide output",
+            Action::ToggleLogs => "show/hide logs",
+            Action::CycleSort => "sort clients by next parameter",
+            Action::Help => "toggle this help",
+        }
+    }
+
+    /// Return whether this action only applies while a confirmation prompt
+    /// is open.
+    pub(crate) fn is_confirmation(self) -> bool {
+        matches!(self, Action::Confirm | Action::Dismiss)
+    }
+
+    /// Look up an action by its key map name, accepting the names used
+    /// before the prompt actions were renamed.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "confirm_quit" => Some(Action::Confirm),
+            "cancel_quit" => Some(Action::Dismiss),
+            _ => Self::ALL
+                .iter()
+                .copied()
+                .find(|action| action.name() == name),
+        }
+    }
+}
+
+/// A key plus modifiers, such as `q`, `Esc`, or `Ctrl+c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// A plain character key without modifiers.
+    pub fn char(c: char) -> Self {
+        Self {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// A key from crossterm's key code and modifiers.
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Return whether a terminal key event corresponds to this key.
+    ///
+    /// Shift is folded into characters because terminals disagree on whether
+    /// they report it for symbols such as `?`.
+    pub(crate) fn matches(&self, event: &KeyEvent) -> bool {
+        self.normalized() == Key::new(event.code, event.modifiers).normalized()
+    }
+
+    /// Fold Shift into character keys, so `Shift+p` is the key `P`.
+    fn normalized(self) -> Self {
+        match self.code {
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers: self.modifiers.difference(KeyModifiers::SHIFT),
+            },
+            _ => self,
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = self.normalized();
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if key.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("Shift+")?;
+        }
+        match key.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Delete"),
+            KeyCode::Insert => f.write_str("Insert"),
+            KeyCode::Up => f.write_str("Up"),
+            KeyCode::Down => f.write_str("Down"),
+            KeyCode::Left => f.write_str("Left"),
+            KeyCode::Right => f.write_str("Right"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PageUp"),
+            KeyCode::PageDown => f.write_str("PageDown"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parse keys like `q`, `?`, `Esc`, `PageUp`, `F5`, or `Ctrl+c`.
+    ///
+    /// `Shift+` with a letter gives the uppercase letter. With any other
+    /// character it is rejected, since the character it produces depends on
+    /// the keyboard layout; write that character instead, such as `?`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.trim().split('+').collect();
+        // Two trailing empty parts mean the key itself is `+`, as in `Ctrl++`.
+        // Any other empty part, as in `a+` or `Ctrl+`, is rejected below.
+        if parts.len() > 1 && parts[parts.len() - 2..] == ["", ""] {
+            parts.pop();
+            parts.pop();
+            parts.push("+");
+        }
+        let (name, modifier_names) = parts.split_last().ok_or_else(|| s.to_string())?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_names {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(s.to_string()),
+            };
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                lower => match lower.strip_prefix('f').map(str::parse::<u8>) {
+                    Some(Ok(n)) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(s.to_string()),
+                },
+            },
+        };
+        if let KeyCode::Char(c) = code {
+            if modifiers.contains(KeyModifiers::SHIFT) && !c.is_ascii_alphabetic() {
+                return Err(s.to_string());
+            }
+        }
+        Ok(Self { code, modifiers }.normalized())
+    }
+}
+
+/// Errors that can occur when loading a key map.
+#[derive(Debug)]
+pub enum KeyMapError {
+    /// The key map file could not be read.
+    Io(io::Error),
+    /// A line was not of the form `action = key, key`.
+    Syntax { line: usize },
+    /// A line named an action that does not exist.
+    UnknownAction { line: usize, name: String },
+    /// A line contained a key that could not be parsed.
+    InvalidKey { line: usize, key: String },
+}
+
+impl fmt::Display for KeyMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyMapError::Io(err) => write!(f, "cannot read key map: {err}"),
+            KeyMapError::Syntax { line } => {
+                write!(f, "line {line}: expected `action = key, key`")
+            }
+            KeyMapError::UnknownAction { line, name } => {
+                write!(f, "line {line}: unknown action `{name}`")
+            }
+            KeyMapError::InvalidKey { line, key } => write!(f, "line {line}: invalid key `{key}`"),
+        }
+    }
+}
+
+impl std::error::Error for KeyMapError {}
+
+/// Mapping from keys to runtime actions.
+///
+/// Start from `KeyMap::default()` and rebind with `bind`, or load a file of
+/// `action = key, key` lines with `KeyMap::load`. Actions missing from a
+/// file keep their default keys.
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: Vec<(Action, Key)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::empty()
+            .bind(Action::Quit, Key::char('q'))
+            .bind(Action::Confirm, Key::char('y'))
+            .bind(Action::Dismiss, Key::char('n'))
+            .bind(Action::Dismiss, Key::new(KeyCode::Esc, KeyModifiers::NONE))
+            .bind(Action::ScrollUp, Key::new(KeyCode::PageUp, KeyModifiers::NONE))
+            .bind(Action::ScrollDown, Key::new(KeyCode::PageDown, KeyModifiers::NONE))
+            .bind(Action::ScrollBottom, Key::new(KeyCode::End, KeyModifiers::NONE))
+            .bind(Action::SelectPrev, Key::new(KeyCode::Up, KeyModifiers::NONE))
+            .bind(Action::SelectNext, Key::new(KeyCode::Down, KeyModifiers::NONE))
+            .bind(Action::Pause, Key::char('p'))
+            .bind(Action::Resume, Key::char('u'))
+            .bind(Action::PauseAll, Key::char('P'))
+            .bind(Action::ResumeAll, Key::char('U'))
+            .bind(Action::Cancel, Key::char('x'))
+            .bind(Action::ToggleSummary, Key::char('s'))
+            .bind(Action::ToggleDetail, Key::char('d'))
+            .bind(Action::ToggleResources, Key::char('r'))
+            .bind(Action::ToggleOutput, Key::char('o'))
+            .bind(Action::ToggleLogs, Key::char('l'))
+            .bind(Action::CycleSort, Key::char('g'))
+            .bind(Action::Help, Key::char('?'))
+    }
+}
+
+impl KeyMap {
+    /// A key map with no bindings at all.
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Add `key` as a binding for `action`, keeping existing bindings.
+    pub fn bind(mut self, action: Action, key: Key) -> Self {
+        self.bindings.push((action, key));
+        self
+    }
+
+    /// Remove every binding for `action`.
+    pub fn unbind(mut self, action: Action) -> Self {
+        self.bindings.retain(|(bound, _)| *bound != action);
+        self
+    }
+
+    /// Load a key map file on top of the default bindings.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeyMapError> {
+        let text = fs::read_to_string(path).map_err(KeyMapError::Io)?;
+        Self::default().apply_config(&text)
+    }
+
+    /// Apply `action = key, key` lines, replacing the keys of each listed
+    /// action. Blank lines and lines starting with `#` are ignored.
+    pub fn apply_config(mut self, text: &str) -> Result<Self, KeyMapError> {
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let raw = raw.trim();
+            if raw.is_empty() || raw.starts_with('#') {
+                continue;
+            }
+            let (name, keys) = raw.split_once('=').ok_or(KeyMapError::Syntax { line })?;
+            let name = name.trim();
+            let action = Action::from_name(name).ok_or_else(|| KeyMapError::UnknownAction {
+                line,
+                name: name.to_string(),
+            })?;
+
+            self = self.unbind(action);
+            for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+                let key = key
+                    .parse()
+                    .map_err(|key| KeyMapError::InvalidKey { line, key })?;
+                self = self.bind(action, key);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Return the keys bound to `action`.
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = Key> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(bound, _)| *bound == action)
+            .map(|(_, key)| *key)
+    }
+
+    /// Return the keys bound to `action` joined for display, or `-`.
+    pub(crate) fn describe(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).map(|key| key.to_string()).collect();
+        if keys.is_empty() {
+            "-".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+
+    /// Resolve a key event to an action.
+    ///
+    /// While `confirming`, only confirmation actions apply; otherwise they
+    /// are ignored, so the same key can serve both contexts.
+    pub(crate) fn action_for(&self, event: &KeyEvent, confirming: bool) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(action, key)| action.is_confirmation() == confirming && key.matches(event))
+            .map(|(action, _)| *action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        s.parse()
+            .unwrap_or_else(|err| panic!("{err:?} should parse"))
+    }
+
+    #[test]
+    fn keys_round_trip_through_display() {
+        for text in [
+            "q", "?", "+", "Space", "Esc", "PageUp", "F5", "Ctrl+c", "Ctrl++", "Alt+Up",
+        ] {
+            assert_eq!(key(text).to_string(), text);
+            assert_eq!(key(&key(text).to_string()), key(text));
+        }
+        assert_eq!(key("ctrl+pgdn").to_string(), "Ctrl+PageDown");
+    }
+
+    #[test]
+    fn shift_is_folded_into_letters() {
+        assert_eq!(key("Shift+p"), Key::char('P'));
+        assert_eq!(key("Ctrl+Shift+x").to_string(), "Ctrl+X");
+        assert_eq!(key("Shift+Up").to_string(), "Shift+Up");
+
+        let shifted = KeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT);
+        let plain = KeyEvent::new(KeyCode::Char('P'), KeyModifiers::NONE);
+        assert!(key("Shift+p").matches(&shifted));
+        assert!(key("P").matches(&plain));
+        assert!(!key("p").matches(&shifted));
+    }
+
+    #[test]
+    fn invalid_keys_are_rejected() {
+        for text in [
+            "", "Shift+1", "Shift+Space", "Hyper+q", "F13", "Escape2", "a+", "Ctrl+", "+q", "++",
+        ] {
+            assert!(text.parse::<Key>().is_err(), "{text:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn config_lines_rebind_actions() {
+        let keys = KeyMap::default()
+            .apply_config("# comment\n\nquit = Shift+x, Esc\n")
+            .expect("valid config");
+        assert_eq!(keys.describe(Action::Quit), "X/Esc");
+
+        let err = KeyMap::default().apply_config("quit = q\npause = Shift+1");
+        assert!(matches!(err, Err(KeyMapError::InvalidKey { line: 2, .. })));
+        let keys = KeyMap::default()
+            .apply_config("confirm_quit = Enter\ncancel_quit = Backspace\n")
+            .expect("old prompt action names are still accepted");
+        assert_eq!(keys.describe(Action::Confirm), "Enter");
+        assert_eq!(keys.describe(Action::Dismiss), "Backspace");
+
+        let err = KeyMap::default().apply_config("jump = j");
+        assert!(matches!(
+            err,
+            Err(KeyMapError::UnknownAction { line: 1, .. })
+        ));
+    }
+}
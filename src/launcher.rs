@@ -0,0 +1,530 @@
+//! ==============================================================================
+//! src/launcher.rs
+//! Builder that owns every option for launching a set of tasks.
+//! ==============================================================================
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::run_report::{Exporter, RunReport, TaskReport};
+use crate::task_group::{describe, run_task, Description, DEFAULT_CHANNEL_CAPACITY};
+use crate::{
+    Backpressure, ClientReporter, ClientStore, IdAllocation, RetryPolicy, Task, TaskError, TaskId,
+    TaskOutputs,
+};
+#[cfg(feature = "tui")]
+use crate::{EndBehavior, Runtime};
+
+/// Position in the launch, label, and output of a task that has run.
+pub(crate) type Finished<O> = (usize, (String, Result<O, TaskError>));
+
+/// How often the store is drained while no TUI is drawing.
+pub(crate) const HEADLESS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time from the start of a launch to its last task finishing, shared by
+/// the workers so the report leaves out time the TUI stays open afterwards.
+#[derive(Debug, Clone)]
+pub(crate) struct FinishClock {
+    started: Instant,
+    /// Nanoseconds from `started` to the latest finish recorded.
+    last: Arc<AtomicU64>,
+}
+
+impl FinishClock {
+    /// Start timing a launch.
+    pub(crate) fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            last: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Record that a task has just finished.
+    pub(crate) fn record(&self) {
+        let elapsed = u64::try_from(self.started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.last.fetch_max(elapsed, Ordering::Relaxed);
+    }
+
+    /// Return the time from the start to the last recorded finish.
+    pub(crate) fn wall_time(&self) -> Duration {
+        Duration::from_nanos(self.last.load(Ordering::Relaxed))
+    }
+}
+
+/// How a launch shows progress.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Renderer {
+    /// Full-screen dashboard on the alternate screen; the default with the
+    /// `tui` feature.
+    #[cfg(feature = "tui")]
+    #[default]
+    Dashboard,
+    /// Compact viewport at the bottom of the terminal (see `Runtime::inline`).
+    #[cfg(feature = "tui")]
+    Inline { height: u16 },
+    /// No TUI; the launch only waits for the tasks and returns the report.
+    #[cfg_attr(not(feature = "tui"), default)]
+    Headless,
+}
+
+/// Outputs and report of a finished launch.
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct LaunchOutcome<O> {
+    /// Output of each task, with its label, in the order the tasks were given.
+    pub outputs: TaskOutputs<O>,
+    /// How each task ended.
+    pub report: RunReport,
+}
+
+/// Runs a vector of tasks on worker threads and reports them to a TUI.
+///
+/// Every option has a default, so `Launcher::new().launch(tasks)` behaves
+/// like `launch_tasks(tasks, 20, None)`. With the `rayon` or `tokio` feature,
+/// `launch_rayon` and `launch_async` run the tasks on a rayon pool or a tokio
+/// runtime instead, with the same options.
+pub struct Launcher {
+    fps: u64,
+    label: Option<String>,
+    quit_on_q: bool,
+    /// Worker threads; `None` runs every task on its own thread.
+    pub(crate) concurrency: Option<usize>,
+    renderer: Renderer,
+    #[cfg(feature = "tui")]
+    end_behavior: EndBehavior,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) ids: IdAllocation,
+    exporters: Vec<Box<dyn Exporter>>,
+    /// Store to report into, with its reporter; `None` creates one per launch.
+    channel: Option<(ClientReporter, ClientStore)>,
+    /// Further runtime settings such as the theme or layout.
+    #[cfg(feature = "tui")]
+    configure_runtime: Option<Box<dyn FnOnce(Runtime) -> Runtime>>,
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Launcher {
+    /// Create a launcher with the default options: 20 fps, no label, one
+    /// thread per task, the default renderer, and no retries.
+    pub fn new() -> Self {
+        Self {
+            fps: 20,
+            label: None,
+            quit_on_q: true,
+            concurrency: None,
+            renderer: Renderer::default(),
+            #[cfg(feature = "tui")]
+            end_behavior: EndBehavior::default(),
+            retry: RetryPolicy::default(),
+            ids: IdAllocation::default(),
+            exporters: Vec::new(),
+            channel: None,
+            #[cfg(feature = "tui")]
+            configure_runtime: None,
+        }
+    }
+
+    /// Set the target frames per second of the TUI.
+    pub fn fps(mut self, fps: u64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Set the project label shown at the top of the TUI.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Enable or disable quitting from the TUI with the quit key.
+    pub fn quit_on_q(mut self, enabled: bool) -> Self {
+        self.quit_on_q = enabled;
+        self
+    }
+
+    /// Run at most `workers` tasks at a time; the rest wait in order.
+    pub fn concurrency(mut self, workers: usize) -> Self {
+        self.concurrency = Some(workers.max(1));
+        self
+    }
+
+    /// Choose how progress is shown.
+    pub fn renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Choose what the TUI does once every task has finished.
+    #[cfg(feature = "tui")]
+    pub fn on_finish(mut self, behavior: EndBehavior) -> Self {
+        self.end_behavior = behavior;
+        self
+    }
+
+    /// Set how iterations that panic are retried.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Choose how tasks get their client ids.
+    ///
+    /// `IdAllocation::Sequential` numbers tasks in the order they were given
+    /// rather than the order they start, so ids match between runs even with
+    /// several workers.
+    pub fn ids(mut self, ids: IdAllocation) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Add an exporter that receives the report once the launch finishes.
+    ///
+    /// Exporters run in the order they were added.
+    pub fn exporter(mut self, exporter: impl Exporter + 'static) -> Self {
+        self.exporters.push(Box::new(exporter));
+        self
+    }
+
+    /// Report into `store` through `reporter`, a pair created together by
+    /// `ClientStore::new` or `ClientStore::bounded`.
+    ///
+    /// Keep a clone of `reporter` to feed the same run from elsewhere, for
+    /// example a `DashboardLogger` whose global records then reach the Logs
+    /// pane. Clients started through it also appear in the TUI and the
+    /// report. By default each launch creates its own bounded store, or an
+    /// unbounded one for `launch_async`.
+    pub fn store(mut self, reporter: ClientReporter, store: ClientStore) -> Self {
+        self.channel = Some((reporter, store));
+        self
+    }
+
+    /// Adjust the runtime further, for example its theme, layout, or key map.
+    ///
+    /// `configure` receives the runtime with the launcher's options applied.
+    #[cfg(feature = "tui")]
+    pub fn runtime(mut self, configure: impl FnOnce(Runtime) -> Runtime + 'static) -> Self {
+        self.configure_runtime = Some(Box::new(configure));
+        self
+    }
+
+    /// Run `tasks` until each completes, fails, or is canceled, and return
+    /// their outputs and the report.
+    ///
+    /// Tasks still queued when the TUI ends are reported as never started.
+    /// Without tasks, no TUI is shown and the report is empty.
+    /// Exporters receive the report even when the TUI ends with an error;
+    /// the error is returned after they have run.
+    pub fn launch<T: Task>(
+        mut self,
+        tasks: Vec<T>,
+    ) -> Result<LaunchOutcome<T::Output>, Box<dyn std::error::Error + Send + Sync>> {
+        let task_count = tasks.len();
+        let clock = FinishClock::start();
+        let (reporter, mut store) = self.open_store();
+        let queue = Arc::new(Mutex::new(
+            tasks.into_iter().enumerate().collect::<VecDeque<_>>(),
+        ));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Shared rather than returned by each worker, so outputs survive a
+        // worker that panics.
+        let finished = Arc::new(Mutex::new(Vec::with_capacity(task_count)));
+
+        let workers = self.concurrency.unwrap_or(task_count).min(task_count);
+        let handles: Vec<JoinHandle<()>> = (0..workers)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let stop = Arc::clone(&stop);
+                let finished = Arc::clone(&finished);
+                let reporter = reporter.clone();
+                let retry = self.retry;
+                let ids = self.ids;
+                let clock = clock.clone();
+                thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        let next = queue.lock().ok().and_then(|mut queue| queue.pop_front());
+                        let Some((index, task)) = next else {
+                            break;
+                        };
+                        let output = run_task(task, &reporter, &retry, task_id(ids, index));
+                        clock.record();
+                        if let Ok(mut finished) = finished.lock() {
+                            finished.push((index, output));
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let outcome = self.supervise(&mut store, task_count, &stop, || {
+            handles.iter().all(JoinHandle::is_finished)
+        });
+        for handle in handles {
+            let _ = handle.join();
+        }
+
+        let mut outputs =
+            std::mem::take(&mut *finished.lock().unwrap_or_else(|err| err.into_inner()));
+        let mut skipped = Vec::new();
+        if let Ok(mut queue) = queue.lock() {
+            for (index, task) in queue.drain(..) {
+                skip(index, describe(&task), &mut outputs, &mut skipped);
+            }
+        }
+        self.finish(store, &clock, task_count, outputs, skipped, outcome)
+    }
+
+    /// Take the store to report into, creating one if none was given, with a
+    /// reporter that allocates ids as configured.
+    pub(crate) fn open_store(&mut self) -> (ClientReporter, ClientStore) {
+        let (reporter, store) = self.channel.take().unwrap_or_else(|| {
+            ClientStore::bounded(DEFAULT_CHANNEL_CAPACITY, Backpressure::DropProgress)
+        });
+        (reporter.with_ids(self.ids), store)
+    }
+
+    /// Like `open_store`, but create an unbounded store if none was given,
+    /// since a full bounded one would stall an async launch.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn open_unbounded_store(&mut self) -> (ClientReporter, ClientStore) {
+        if self.channel.is_none() {
+            self.channel = Some(ClientStore::new());
+        }
+        self.open_store()
+    }
+
+    /// Show progress until the TUI ends, or until `done` when headless; then
+    /// set `stop` and ask every client to cancel until `done`.
+    ///
+    /// Returns at once when there are no tasks.
+    pub(crate) fn supervise(
+        &mut self,
+        store: &mut ClientStore,
+        task_count: usize,
+        stop: &AtomicBool,
+        done: impl Fn() -> bool,
+    ) -> std::io::Result<()> {
+        // The TUI would wait for at least one client and never finish.
+        if task_count == 0 {
+            return Ok(());
+        }
+        #[cfg(feature = "tui")]
+        let outcome = match self.build_runtime(task_count) {
+            Some(mut runtime) => runtime.run(store),
+            None => {
+                wait_headless(store, &done);
+                Ok(())
+            }
+        };
+        #[cfg(not(feature = "tui"))]
+        let outcome = {
+            let _ = task_count;
+            wait_headless(store, &done);
+            Ok(())
+        };
+        // Queued tasks never start once the TUI has ended; running ones are
+        // asked to cancel, including any that started after the TUI did so.
+        stop.store(true, Ordering::Relaxed);
+        while !done() {
+            store.drain();
+            store.cancel_all();
+            thread::sleep(HEADLESS_POLL_INTERVAL);
+        }
+        outcome
+    }
+
+    /// Like `supervise`, but paced with `tokio::time` so the tasks keep
+    /// running on the same executor.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn supervise_async(
+        &mut self,
+        store: &mut ClientStore,
+        task_count: usize,
+        stop: &AtomicBool,
+        done: impl Fn() -> bool,
+    ) -> std::io::Result<()> {
+        if task_count == 0 {
+            return Ok(());
+        }
+        #[cfg(feature = "tui")]
+        if let Some(mut runtime) = self.build_runtime(task_count) {
+            let outcome = runtime.run_async(store).await;
+            // As in `supervise`: nothing new starts and the rest is canceled.
+            stop.store(true, Ordering::Relaxed);
+            while !done() {
+                store.drain();
+                store.cancel_all();
+                tokio::time::sleep(HEADLESS_POLL_INTERVAL).await;
+            }
+            return outcome;
+        }
+        #[cfg(not(feature = "tui"))]
+        let _ = (task_count, stop);
+        while !done() {
+            store.drain();
+            tokio::time::sleep(HEADLESS_POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    /// Build the runtime that shows a launch of `task_count` tasks, or `None`
+    /// when headless.
+    #[cfg(feature = "tui")]
+    fn build_runtime(&mut self, task_count: usize) -> Option<Runtime> {
+        let height = match self.renderer {
+            Renderer::Dashboard => None,
+            Renderer::Inline { height } => Some(height),
+            Renderer::Headless => return None,
+        };
+        let mut runtime = Runtime::new(self.fps)
+            .quit_on_q(self.quit_on_q)
+            .expect_clients(task_count)
+            .on_finish(self.end_behavior);
+        if let Some(label) = self.label.take() {
+            runtime = runtime.project_label(label);
+        }
+        if let Some(height) = height {
+            runtime = runtime.inline(height);
+        }
+        if let Some(configure) = self.configure_runtime.take() {
+            runtime = configure(runtime);
+        }
+        Some(runtime)
+    }
+
+    /// Build the report of a launch, hand it to the exporters, and return it
+    /// with the outputs in the order the tasks were given.
+    ///
+    /// `skipped` holds the reports of tasks that never started, by position
+    /// in the launch. A task of the `task_count` without an output was lost
+    /// to a worker that panicked, and fails. `outcome` is how the TUI ended;
+    /// its error is returned after the exporters have run.
+    pub(crate) fn finish<O>(
+        mut self,
+        mut store: ClientStore,
+        clock: &FinishClock,
+        task_count: usize,
+        mut outputs: Vec<Finished<O>>,
+        mut skipped: Vec<(usize, TaskReport)>,
+        outcome: std::io::Result<()>,
+    ) -> Result<LaunchOutcome<O>, Box<dyn std::error::Error + Send + Sync>> {
+        store.drain();
+        let mut report = RunReport::new(&store, clock.wall_time());
+        skipped.sort_by_key(|(index, _)| *index);
+        report
+            .tasks
+            .extend(skipped.into_iter().map(|(_, report)| report));
+        outputs.sort_by_key(|(index, _)| *index);
+        let mut outputs = outputs.into_iter().peekable();
+        let outputs: TaskOutputs<O> = (0..task_count)
+            .map(|index| match outputs.next_if(|(next, _)| *next == index) {
+                Some((_, output)) => output,
+                None => {
+                    let message = "the task's worker panicked".to_string();
+                    (String::new(), Err(TaskError::Panicked(message)))
+                }
+            })
+            .collect();
+
+        for exporter in &mut self.exporters {
+            exporter.export(&report)?;
+        }
+        outcome?;
+        Ok(LaunchOutcome { outputs, report })
+    }
+}
+
+/// Record the task at `index` as never started, from its `describe`: its
+/// output, and its report unless describing it panicked.
+pub(crate) fn skip<O>(
+    index: usize,
+    description: Result<Description, String>,
+    outputs: &mut Vec<Finished<O>>,
+    skipped: &mut Vec<(usize, TaskReport)>,
+) {
+    match description {
+        Ok((label, total, params)) => {
+            skipped.push((index, TaskReport::not_started(&label, params, total)));
+            outputs.push((index, (label, Err(TaskError::NotStarted))));
+        }
+        Err(message) => outputs.push((index, (String::new(), Err(TaskError::Panicked(message))))),
+    }
+}
+
+/// Return the client id of the task at `index` in a launch, or `None` to let
+/// the reporter pick one.
+pub(crate) fn task_id(ids: IdAllocation, index: usize) -> Option<TaskId> {
+    (ids == IdAllocation::Sequential).then_some(TaskId(index as u64))
+}
+
+/// Drain the store until `done`, so a bounded channel never blocks the tasks.
+fn wait_headless(store: &mut ClientStore, done: impl Fn() -> bool) {
+    while !done() {
+        store.drain();
+        thread::sleep(HEADLESS_POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Task that panics in `total_iters` when `broken`, before it starts.
+    pub(crate) struct Fragile {
+        pub(crate) broken: bool,
+    }
+
+    impl Task for Fragile {
+        type Output = u64;
+
+        fn label(&self) -> &str {
+            "fragile"
+        }
+
+        fn total_iters(&self) -> u64 {
+            assert!(!self.broken, "no total");
+            2
+        }
+
+        fn workload_per_iter(&mut self) {}
+
+        fn finish(self) -> u64 {
+            7
+        }
+    }
+
+    pub(crate) fn fragile_tasks() -> Vec<Fragile> {
+        [false, true, false].map(|broken| Fragile { broken }).into()
+    }
+
+    /// Check that every task has an output and only the broken one failed.
+    pub(crate) fn assert_one_output_per_task(outputs: &TaskOutputs<u64>) {
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[0], ("fragile".to_string(), Ok(7)));
+        assert!(
+            matches!(&outputs[1].1, Err(TaskError::Panicked(message)) if message == "no total")
+        );
+        assert_eq!(outputs[2], ("fragile".to_string(), Ok(7)));
+    }
+
+    #[test]
+    fn a_task_that_panics_before_starting_still_has_an_output() {
+        let outcome = Launcher::new()
+            .renderer(Renderer::Headless)
+            .concurrency(1)
+            .launch(fragile_tasks())
+            .expect("launch");
+
+        assert_one_output_per_task(&outcome.outputs);
+        assert_eq!(outcome.report.tasks.len(), 2);
+    }
+}
@@ -0,0 +1,283 @@
+//! ==============================================================================
+//! src/layout.rs
+//! Panel and client table layout configuration for the runtime TUI.
+//! ==============================================================================
+
+use ratatui::layout::Constraint;
+
+/// A panel the runtime can draw.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Panel {
+    /// Project label and elapsed time.
+    Header,
+    /// Client counts by status and overall progress.
+    Summary,
+    /// Process CPU, RSS, and threads (see `Runtime::monitor_resources`).
+    Resources,
+    /// One row per client.
+    Clients,
+    /// Everything known about the selected client, including its messages.
+    Detail,
+    /// Captured stdout/stderr (see `Runtime::capture_output`).
+    Output,
+    /// The store's global log buffer.
+    Logs,
+    /// Key hints and prompts.
+    Controls,
+}
+
+/// One panel slot in a `DashboardLayout`.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    panel: Panel,
+    size: Constraint,
+    visible: bool,
+}
+
+/// Ordered, sized, and toggleable set of panels stacked top to bottom.
+///
+/// Panels without content (no logs yet, output capture off, resources not
+/// monitored) are skipped when drawing, so their space goes to the others.
+/// Hidden panels stay in place and can be toggled on at runtime.
+#[derive(Debug, Clone)]
+pub struct DashboardLayout {
+    slots: Vec<Slot>,
+}
+
+impl Default for DashboardLayout {
+    fn default() -> Self {
+        Self::empty()
+            .panel(Panel::Header, Constraint::Length(3))
+            .hidden(Panel::Summary, Constraint::Length(3))
+            .panel(Panel::Resources, Constraint::Length(5))
+            .panel(Panel::Clients, Constraint::Fill(3))
+            .hidden(Panel::Detail, Constraint::Length(10))
+            .panel(Panel::Output, Constraint::Fill(1))
+            .panel(Panel::Logs, Constraint::Fill(1))
+            .panel(Panel::Controls, Constraint::Length(3))
+    }
+}
+
+impl DashboardLayout {
+    /// A layout without any panels.
+    pub fn empty() -> Self {
+        Self { slots: Vec::new() }
+    }
+
+    /// Append a visible panel with the given height constraint.
+    ///
+    /// A panel that is already present is moved to the end.
+    pub fn panel(self, panel: Panel, size: Constraint) -> Self {
+        self.push(panel, size, true)
+    }
+
+    /// Append a panel that starts hidden and can be toggled on at runtime.
+    pub fn hidden(self, panel: Panel, size: Constraint) -> Self {
+        self.push(panel, size, false)
+    }
+
+    /// Remove a panel entirely.
+    pub fn without(mut self, panel: Panel) -> Self {
+        self.slots.retain(|slot| slot.panel != panel);
+        self
+    }
+
+    /// Return whether `panel` is present and visible.
+    pub fn is_visible(&self, panel: Panel) -> bool {
+        self.slots
+            .iter()
+            .any(|slot| slot.panel == panel && slot.visible)
+    }
+
+    /// Show or hide a panel that is part of the layout.
+    pub fn set_visible(&mut self, panel: Panel, visible: bool) {
+        for slot in self.slots.iter_mut().filter(|slot| slot.panel == panel) {
+            slot.visible = visible;
+        }
+    }
+
+    /// Flip the visibility of a panel that is part of the layout.
+    pub(crate) fn toggle(&mut self, panel: Panel) {
+        let visible = self.is_visible(panel);
+        self.set_visible(panel, !visible);
+    }
+
+    /// Return visible panels and their sizes, top to bottom.
+    pub(crate) fn visible(&self) -> impl Iterator<Item = (Panel, Constraint)> + '_ {
+        self.slots
+            .iter()
+            .filter(|slot| slot.visible)
+            .map(|slot| (slot.panel, slot.size))
+    }
+
+    fn push(mut self, panel: Panel, size: Constraint, visible: bool) -> Self {
+        self = self.without(panel);
+        self.slots.push(Slot {
+            panel,
+            size,
+            visible,
+        });
+        self
+    }
+}
+
+/// A column of the clients table.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// Client label.
+    Label,
+    /// Lifecycle status.
+    Status,
+    /// `current/total` iterations.
+    Count,
+    /// Percent complete.
+    Percent,
+    /// Progress bar, or a throbber when the total is unknown.
+    Bar,
+    /// Average iterations per second since the client started.
+    Rate,
+    /// Estimated time remaining at the average rate.
+    Eta,
+    /// Time since the client started, frozen once it finishes.
+    Elapsed,
+    /// Duration of the most recent iteration.
+    LastIter,
+    /// CPU usage of the client's worker thread (see `Runtime::monitor_resources`).
+    Cpu,
+    /// One column per metric set with `ClientHandle::set_metric`.
+    Metrics,
+    /// The client's most recent log message.
+    Message,
+}
+
+impl Column {
+    /// Header text shown above the column.
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Label => "Label",
+            Column::Status => "Status",
+            Column::Count => "Progress",
+            Column::Percent => "%",
+            Column::Bar => "",
+            Column::Rate => "Rate",
+            Column::Eta => "ETA",
+            Column::Elapsed => "Elapsed",
+            Column::LastIter => "Last",
+            Column::Cpu => "CPU",
+            Column::Metrics => "",
+            Column::Message => "Message",
+        }
+    }
+
+    /// Rank in which columns give way on narrow terminals; lower goes first
+    /// and `None` is always kept.
+    pub(crate) fn drop_rank(self) -> Option<u8> {
+        match self {
+            Column::Message => Some(1),
+            Column::Metrics => Some(2),
+            Column::Cpu => Some(3),
+            Column::Rate => Some(4),
+            Column::Eta => Some(5),
+            Column::Elapsed => Some(6),
+            Column::LastIter => Some(7),
+            Column::Bar => Some(8),
+            Column::Count => Some(9),
+            Column::Label | Column::Status | Column::Percent => None,
+        }
+    }
+}
+
+/// One column slot in `ClientColumns`.
+#[derive(Debug, Clone, Copy)]
+struct ColumnSlot {
+    column: Column,
+    /// Explicit width; `None` sizes the column to its content.
+    width: Option<Constraint>,
+    visible: bool,
+}
+
+/// Ordered set of columns shown in the clients table, with optional widths.
+///
+/// Columns without an explicit width are sized to their content, except the
+/// bar, which takes the remaining space. When the table is wider than the
+/// panel, optional columns are dropped, starting with the message.
+#[derive(Debug, Clone)]
+pub struct ClientColumns {
+    slots: Vec<ColumnSlot>,
+}
+
+impl Default for ClientColumns {
+    fn default() -> Self {
+        Self::empty()
+            .column(Column::Label)
+            .column(Column::Status)
+            .column(Column::Count)
+            .column(Column::Bar)
+            .column(Column::Percent)
+            .column(Column::Rate)
+            .column(Column::Eta)
+            .column(Column::Elapsed)
+            .column(Column::LastIter)
+            .column(Column::Cpu)
+            .column(Column::Metrics)
+            .column(Column::Message)
+    }
+}
+
+impl ClientColumns {
+    /// A table without any columns.
+    pub fn empty() -> Self {
+        Self { slots: Vec::new() }
+    }
+
+    /// Append a visible column sized to its content.
+    ///
+    /// A column that is already present is moved to the end.
+    pub fn column(mut self, column: Column) -> Self {
+        self = self.without(column);
+        self.slots.push(ColumnSlot {
+            column,
+            width: None,
+            visible: true,
+        });
+        self
+    }
+
+    /// Set an explicit width for a column that is part of the table.
+    pub fn width(mut self, column: Column, width: Constraint) -> Self {
+        for slot in self.slots.iter_mut().filter(|slot| slot.column == column) {
+            slot.width = Some(width);
+        }
+        self
+    }
+
+    /// Remove a column entirely.
+    pub fn without(mut self, column: Column) -> Self {
+        self.slots.retain(|slot| slot.column != column);
+        self
+    }
+
+    /// Return whether `column` is present and visible.
+    pub fn is_visible(&self, column: Column) -> bool {
+        self.slots
+            .iter()
+            .any(|slot| slot.column == column && slot.visible)
+    }
+
+    /// Show or hide a column that is part of the table.
+    pub fn set_visible(&mut self, column: Column, visible: bool) {
+        for slot in self.slots.iter_mut().filter(|slot| slot.column == column) {
+            slot.visible = visible;
+        }
+    }
+
+    /// Return visible columns and their explicit widths, left to right.
+    pub(crate) fn visible(&self) -> impl Iterator<Item = (Column, Option<Constraint>)> + '_ {
+        self.slots
+            .iter()
+            .filter(|slot| slot.visible)
+            .map(|slot| (slot.column, slot.width))
+    }
+}
@@ -1,15 +1,67 @@
 mod client_state;
+mod client_control;
 mod client_reporter;
 mod client_store;
 mod task;
 mod task_group;
+mod launcher;
+mod retry;
+mod run_report;
+mod sweep;
 #[cfg(feature = "tui")]
 mod runtime;
+#[cfg(all(feature = "tui", unix))]
+mod output_capture;
+#[cfg(feature = "tui")]
+mod resources;
+#[cfg(feature = "tui")]
+mod client_table;
+#[cfg(feature = "tui")]
+mod gauge;
+#[cfg(feature = "tui")]
+mod key_map;
+#[cfg(feature = "tui")]
+mod layout;
+#[cfg(feature = "tui")]
+mod summary;
+#[cfg(feature = "tui")]
+mod terminal_guard;
+#[cfg(feature = "tui")]
+mod theme;
+#[cfg(feature = "rayon")]
+mod rayon_progress;
+#[cfg(feature = "tokio")]
+mod async_task;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+#[cfg(feature = "log")]
+mod log_backend;
 
-pub use client_reporter::{ClientHandle, ClientReporter, ReportError};
-pub use client_state::{ClientState, TaskId, TaskStatus};
+pub use client_reporter::{
+    AtomicHandle, Backpressure, ClientGuard, ClientHandle, ClientReporter, CoalescePolicy,
+    CoalescedHandle, ReportError,
+};
+pub use client_state::{ClientState, IdAllocation, Phase, TaskId, TaskStatus};
 pub use client_store::ClientStore;
-pub use task::Task;
+pub use task::{Task, TaskError, TaskOutputs};
 pub use task_group::{launch_tasks, TaskGroup};
+pub use launcher::{LaunchOutcome, Launcher, Renderer};
+pub use retry::RetryPolicy;
+pub use run_report::{Exporter, IterationStats, RunReport, TaskReport};
+pub use sweep::{Params, Sweep, SweepTask};
+#[cfg(feature = "tui")]
+pub use key_map::{Action, Key, KeyMap, KeyMapError};
+#[cfg(feature = "tui")]
+pub use layout::{ClientColumns, Column, DashboardLayout, Panel};
+#[cfg(feature = "tui")]
+pub use runtime::{EndBehavior, Runtime};
 #[cfg(feature = "tui")]
-pub use runtime::Runtime;
+pub use theme::Theme;
+#[cfg(feature = "rayon")]
+pub use rayon_progress::{launch_tasks_rayon, ParallelProgressExt};
+#[cfg(feature = "tokio")]
+pub use async_task::{launch_async_tasks, AsyncTask};
+#[cfg(feature = "tracing")]
+pub use tracing_layer::ProgressLayer;
+#[cfg(feature = "log")]
+pub use log_backend::DashboardLogger;
@@ -0,0 +1,72 @@
+//! ==============================================================================
+//! src/log_backend.rs
+//! `log` facade backend that routes records into the store.
+//! ==============================================================================
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::{ClientHandle, ClientReporter};
+
+/// `log::Log` implementation that feeds the dashboard.
+///
+/// Records emitted on a thread with an entered client (see
+/// `ClientHandle::enter`; the launchers do this for every task) become that
+/// client's messages. Everything else goes to the store's global log buffer;
+/// pass the same store to `Launcher::store` so it shows in the Logs pane of a
+/// launched run. Records the store cannot take are printed to stderr.
+pub struct DashboardLogger {
+    reporter: ClientReporter,
+    level: LevelFilter,
+}
+
+impl DashboardLogger {
+    /// Create a logger that reports into the store behind `reporter`.
+    ///
+    /// Records up to `Info` are captured by default.
+    pub fn new(reporter: ClientReporter) -> Self {
+        Self {
+            reporter,
+            level: LevelFilter::Info,
+        }
+    }
+
+    /// Set the most verbose level that is captured.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Install this logger as the global `log` backend.
+    ///
+    /// Fails if another logger has already been installed.
+    pub fn install(self) -> Result<(), SetLoggerError> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+impl Log for DashboardLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = format!("{} {}: {}", record.level(), record.target(), record.args());
+        let sent = match ClientHandle::current() {
+            Some(client) => client.log(message.clone()).is_ok(),
+            None => self.reporter.try_log(&message),
+        };
+        // Once the run has ended, or if its store is not being drained,
+        // the record goes to stderr rather than nowhere.
+        if !sent {
+            eprintln!("{message}");
+        }
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,192 @@
+//! ==============================================================================
+//! src/output_capture.rs
+//! Redirect stdout/stderr into pipes while the TUI owns the terminal.
+//! ==============================================================================
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::fd::{FromRawFd, RawFd};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Active redirection of fd 1 and fd 2.
+///
+/// While alive, anything the process writes to stdout or stderr is split
+/// into lines for display and recorded verbatim for replay once the terminal
+/// is restored.
+pub(crate) struct OutputCapture {
+    streams: Vec<CapturedStream>,
+    shared: Arc<Mutex<Captured>>,
+}
+
+/// Output collected by the reader threads.
+#[derive(Default)]
+struct Captured {
+    /// Complete lines not yet taken for display.
+    lines: Vec<String>,
+    /// Every chunk read, in arrival order, tagged with its source fd.
+    transcript: Vec<(RawFd, Vec<u8>)>,
+}
+
+/// One redirected file descriptor and the thread reading its pipe.
+struct CapturedStream {
+    fd: RawFd,
+    saved: RawFd,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl OutputCapture {
+    /// Redirect stdout and stderr into pipes.
+    ///
+    /// Returns the capture and a handle to the original stdout.
+    pub(crate) fn start() -> io::Result<(Self, File)> {
+        let _ = io::stdout().flush();
+        let _ = io::stderr().flush();
+
+        let mut capture = Self {
+            streams: Vec::with_capacity(2),
+            shared: Arc::new(Mutex::new(Captured::default())),
+        };
+        for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+            // On error, dropping `capture` restores the streams captured so far.
+            let stream = CapturedStream::start(fd, Arc::clone(&capture.shared))?;
+            capture.streams.push(stream);
+        }
+
+        let terminal = cvt(unsafe { libc::dup(capture.streams[0].saved) })?;
+        // SAFETY: `terminal` is a freshly duplicated descriptor owned by nobody else.
+        Ok((capture, unsafe { File::from_raw_fd(terminal) }))
+    }
+
+    /// Take the lines captured since the last call.
+    pub(crate) fn take_lines(&self) -> Vec<String> {
+        self.shared
+            .lock()
+            .map(|mut captured| std::mem::take(&mut captured.lines))
+            .unwrap_or_default()
+    }
+
+    /// Restore stdout/stderr and write everything captured to them.
+    pub(crate) fn replay(mut self) -> io::Result<()> {
+        self.restore();
+        let transcript = self
+            .shared
+            .lock()
+            .map(|mut captured| std::mem::take(&mut captured.transcript))
+            .unwrap_or_default();
+
+        let mut stdout = io::stdout().lock();
+        let mut stderr = io::stderr().lock();
+        for (fd, chunk) in transcript {
+            if fd == libc::STDERR_FILENO {
+                stdout.flush()?;
+                stderr.write_all(&chunk)?;
+            } else {
+                stdout.write_all(&chunk)?;
+            }
+        }
+        stdout.flush()
+    }
+
+    /// Point both streams back at the terminal and drain the pipes.
+    fn restore(&mut self) {
+        let _ = io::stdout().flush();
+        let _ = io::stderr().flush();
+        for stream in &mut self.streams {
+            stream.restore();
+        }
+    }
+}
+
+impl Drop for OutputCapture {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+impl CapturedStream {
+    /// Point `fd` at a new pipe and spawn a thread draining it into `shared`.
+    fn start(fd: RawFd, shared: Arc<Mutex<Captured>>) -> io::Result<Self> {
+        let saved = cvt(unsafe { libc::dup(fd) })?;
+        let mut pipe = [0; 2];
+        if let Err(err) = cvt(unsafe { libc::pipe(pipe.as_mut_ptr()) }) {
+            unsafe { libc::close(saved) };
+            return Err(err);
+        }
+        let [read_end, write_end] = pipe;
+        let redirected = cvt(unsafe { libc::dup2(write_end, fd) });
+        unsafe { libc::close(write_end) };
+        if let Err(err) = redirected {
+            unsafe {
+                libc::close(read_end);
+                libc::close(saved);
+            }
+            return Err(err);
+        }
+
+        // SAFETY: `read_end` is owned exclusively by the reader thread.
+        let pipe = unsafe { File::from_raw_fd(read_end) };
+        let reader = thread::spawn(move || read_pipe(fd, pipe, &shared));
+
+        Ok(Self {
+            fd,
+            saved,
+            reader: Some(reader),
+        })
+    }
+
+    /// Point `fd` back at the original stream and wait for the pipe to drain.
+    ///
+    /// Restoring drops the last write end of the pipe, so the reader sees
+    /// EOF once everything written so far has been consumed.
+    fn restore(&mut self) {
+        let Some(reader) = self.reader.take() else {
+            return;
+        };
+        unsafe {
+            libc::dup2(self.saved, self.fd);
+            libc::close(self.saved);
+        }
+        let _ = reader.join();
+    }
+}
+
+/// Read `pipe` until EOF, recording chunks and splitting complete lines.
+fn read_pipe(fd: RawFd, mut pipe: File, shared: &Mutex<Captured>) {
+    let mut buf = [0u8; 8 * 1024];
+    let mut partial = Vec::new();
+    loop {
+        let n = match pipe.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        };
+        let Ok(mut captured) = shared.lock() else {
+            break;
+        };
+        captured.transcript.push((fd, buf[..n].to_vec()));
+        for &byte in &buf[..n] {
+            if byte == b'\n' {
+                captured.lines.push(String::from_utf8_lossy(&partial).into_owned());
+                partial.clear();
+            } else {
+                partial.push(byte);
+            }
+        }
+    }
+    if !partial.is_empty() {
+        if let Ok(mut captured) = shared.lock() {
+            captured.lines.push(String::from_utf8_lossy(&partial).into_owned());
+        }
+    }
+}
+
+/// Convert a libc return value into an `io::Result`.
+fn cvt(ret: libc::c_int) -> io::Result<RawFd> {
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
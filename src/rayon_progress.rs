@@ -0,0 +1,157 @@
+//! ==============================================================================
+//! src/rayon_progress.rs
+//! Rayon integration: progress adaptor for parallel iterators and a pool launcher.
+//! ==============================================================================
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rayon::iter::ParallelIterator;
+use rayon::ThreadPool;
+
+use crate::launcher::{skip, task_id, FinishClock};
+use crate::retry::catch_panic;
+use crate::task_group::{describe, run_task};
+use crate::{ClientHandle, CoalescePolicy, LaunchOutcome, Launcher, Task};
+
+/// Default minimum time between two progress sends from a parallel iterator.
+const DEFAULT_PUBLISH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Extension trait adding progress reporting to rayon parallel iterators.
+///
+/// Every item yielded by the wrapped iterator counts as one unit of work.
+/// Worker threads only bump the shared counter of a `CoalescedHandle`; a
+/// `set_current` update is sent at most once per publish interval, and a
+/// final update is sent when the iterator is dropped. The handle is not
+/// completed automatically.
+pub trait ParallelProgressExt: ParallelIterator {
+    /// Report progress into `handle` using the default publish interval.
+    fn progress(self, handle: ClientHandle) -> impl ParallelIterator<Item = Self::Item> {
+        self.progress_every(handle, DEFAULT_PUBLISH_INTERVAL)
+    }
+
+    /// Report progress into `handle`, sending at most one update per `interval`.
+    fn progress_every(
+        self,
+        handle: ClientHandle,
+        interval: Duration,
+    ) -> impl ParallelIterator<Item = Self::Item> {
+        let counter = handle.coalesced(CoalescePolicy::every(interval));
+        self.inspect(move |_| {
+            let _ = counter.inc(1);
+        })
+    }
+}
+
+impl<I: ParallelIterator> ParallelProgressExt for I {}
+
+impl Launcher {
+    /// Run `tasks` on a rayon pool until each completes, fails, or is
+    /// canceled, and return their outputs and the report.
+    ///
+    /// Tasks are spawned onto `pool`, or onto the current rayon pool (the
+    /// global one outside of `ThreadPool::install`) when `None`; the pool's
+    /// size limits how many run at once, so `concurrency` does not apply.
+    /// The TUI runs on the calling thread while the tasks execute. Every
+    /// other option applies as for `launch`, and tasks still queued in the
+    /// pool when the TUI ends are reported as never started.
+    pub fn launch_rayon<T: Task>(
+        mut self,
+        tasks: Vec<T>,
+        pool: Option<&ThreadPool>,
+    ) -> Result<LaunchOutcome<T::Output>, Box<dyn std::error::Error + Send + Sync>> {
+        let task_count = tasks.len();
+        let clock = FinishClock::start();
+        let (reporter, mut store) = self.open_store();
+        let outputs = Arc::new(Mutex::new(Vec::with_capacity(task_count)));
+        let skipped = Arc::new(Mutex::new(Vec::new()));
+        let remaining = Arc::new(AtomicUsize::new(task_count));
+        let stop = Arc::new(AtomicBool::new(false));
+        let retry = self.retry;
+        let ids = self.ids;
+
+        let run = |scope: &rayon::Scope<'_>| -> std::io::Result<()> {
+            for (index, task) in tasks.into_iter().enumerate() {
+                let reporter = reporter.clone();
+                let outputs = Arc::clone(&outputs);
+                let skipped = Arc::clone(&skipped);
+                let remaining = Arc::clone(&remaining);
+                let stop = Arc::clone(&stop);
+                let clock = clock.clone();
+                scope.spawn(move |_| {
+                    // A panic here would leave `remaining` above zero forever
+                    // and resurface from the scope; `finish` reports the task
+                    // as panicked instead.
+                    let _ = catch_panic(|| {
+                        if stop.load(Ordering::Relaxed) {
+                            let (Ok(mut outputs), Ok(mut skipped)) =
+                                (outputs.lock(), skipped.lock())
+                            else {
+                                return;
+                            };
+                            skip(index, describe(&task), &mut outputs, &mut skipped);
+                        } else {
+                            let output = run_task(task, &reporter, &retry, task_id(ids, index));
+                            clock.record();
+                            if let Ok(mut outputs) = outputs.lock() {
+                                outputs.push((index, output));
+                            }
+                        }
+                    });
+                    remaining.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+            self.supervise(&mut store, task_count, &stop, || {
+                remaining.load(Ordering::Relaxed) == 0
+            })
+        };
+
+        let outcome = match pool {
+            Some(pool) => pool.in_place_scope(run),
+            None => rayon::in_place_scope(run),
+        };
+
+        let outputs = std::mem::take(&mut *outputs.lock().unwrap_or_else(|err| err.into_inner()));
+        let skipped = std::mem::take(&mut *skipped.lock().unwrap_or_else(|err| err.into_inner()));
+        self.finish(store, &clock, task_count, outputs, skipped, outcome)
+    }
+}
+
+/// Launch a vector of tasks on a rayon pool with standard TUI behavior.
+///
+/// This is shorthand for `Launcher::new().fps(runtime_fps).launch_rayon(tasks,
+/// pool)` with an optional label; use `Launcher` for every other option.
+pub fn launch_tasks_rayon<T: Task>(
+    tasks: Vec<T>,
+    runtime_fps: u64,
+    runtime_project_label: Option<String>,
+    pool: Option<&ThreadPool>,
+) -> Result<LaunchOutcome<T::Output>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut launcher = Launcher::new().fps(runtime_fps);
+    if let Some(label) = runtime_project_label {
+        launcher = launcher.label(label);
+    }
+    launcher.launch_rayon(tasks, pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::tests::{assert_one_output_per_task, fragile_tasks};
+    use crate::Renderer;
+
+    #[test]
+    fn a_task_that_panics_before_starting_still_has_an_output() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .expect("rayon pool");
+        let outcome = Launcher::new()
+            .renderer(Renderer::Headless)
+            .launch_rayon(fragile_tasks(), Some(&pool))
+            .expect("launch");
+
+        assert_one_output_per_task(&outcome.outputs);
+    }
+}
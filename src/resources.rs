@@ -0,0 +1,202 @@
+//! ==============================================================================
+//! src/resources.rs
+//! Process resource sampling (CPU, RSS, threads) from /proc for the runtime.
+//! ==============================================================================
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Number of samples kept for the sparklines.
+const HISTORY_LEN: usize = 120;
+
+/// Latest process-wide resource figures.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ResourceStats {
+    /// Process CPU usage in percent of one core (can exceed 100).
+    pub(crate) cpu_percent: f64,
+    /// Resident set size in bytes.
+    pub(crate) rss_bytes: u64,
+    /// Number of OS threads in the process.
+    pub(crate) threads: u64,
+}
+
+/// Periodic sampler over `/proc/self`.
+///
+/// Sampling is rate-limited to `interval`, so calling `sample` every frame
+/// is cheap. On platforms without procfs every sample is empty.
+pub(crate) struct ResourceSampler {
+    interval: Duration,
+    last_sample: Option<Instant>,
+    /// Process CPU ticks at the previous sample.
+    last_ticks: u64,
+    /// Per-thread CPU ticks at the previous sample, keyed by thread id.
+    last_thread_ticks: HashMap<u64, u64>,
+    clock_ticks: f64,
+    latest: Option<ResourceStats>,
+    thread_cpu: HashMap<u64, f64>,
+    cpu_history: VecDeque<u64>,
+    rss_history: VecDeque<u64>,
+}
+
+impl ResourceSampler {
+    /// Create a sampler that refreshes at most once per `interval`.
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_sample: None,
+            last_ticks: 0,
+            last_thread_ticks: HashMap::new(),
+            clock_ticks: clock_ticks_per_sec(),
+            latest: None,
+            thread_cpu: HashMap::new(),
+            cpu_history: VecDeque::with_capacity(HISTORY_LEN),
+            rss_history: VecDeque::with_capacity(HISTORY_LEN),
+        }
+    }
+
+    /// Take a new sample if the interval has elapsed.
+    pub(crate) fn sample(&mut self) {
+        let now = Instant::now();
+        let wall = match self.last_sample {
+            Some(last) if now.duration_since(last) < self.interval => return,
+            Some(last) => now.duration_since(last).as_secs_f64(),
+            None => 0.0,
+        };
+        self.last_sample = Some(now);
+
+        let Some(ticks) = read_stat_ticks("/proc/self/stat") else {
+            return;
+        };
+        let (rss_bytes, threads) = read_status().unwrap_or_default();
+        let to_percent = |delta: u64| {
+            if wall > 0.0 {
+                delta as f64 / self.clock_ticks / wall * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        let cpu_percent = to_percent(ticks.saturating_sub(self.last_ticks));
+        self.last_ticks = ticks;
+
+        let thread_ticks = read_thread_ticks();
+        self.thread_cpu = thread_ticks
+            .iter()
+            .map(|(tid, ticks)| {
+                let previous = self.last_thread_ticks.get(tid).copied().unwrap_or(*ticks);
+                (*tid, to_percent(ticks.saturating_sub(previous)))
+            })
+            .collect();
+        self.last_thread_ticks = thread_ticks;
+
+        // The first sample has no previous reading to diff against.
+        if wall == 0.0 {
+            return;
+        }
+        self.latest = Some(ResourceStats {
+            cpu_percent,
+            rss_bytes,
+            threads,
+        });
+        push_history(&mut self.cpu_history, cpu_percent.round() as u64);
+        push_history(&mut self.rss_history, rss_bytes);
+    }
+
+    /// Return the latest figures, if at least two samples have been taken.
+    pub(crate) fn latest(&self) -> Option<ResourceStats> {
+        self.latest
+    }
+
+    /// Return CPU usage per OS thread id over the last interval, in percent.
+    pub(crate) fn thread_cpu(&self) -> &HashMap<u64, f64> {
+        &self.thread_cpu
+    }
+
+    /// Return recent process CPU percentages, oldest first.
+    pub(crate) fn cpu_history(&self) -> Vec<u64> {
+        self.cpu_history.iter().copied().collect()
+    }
+
+    /// Return recent RSS values in bytes, oldest first.
+    pub(crate) fn rss_history(&self) -> Vec<u64> {
+        self.rss_history.iter().copied().collect()
+    }
+}
+
+/// Append to a fixed-length history, dropping the oldest sample.
+fn push_history(history: &mut VecDeque<u64>, value: u64) {
+    if history.len() == HISTORY_LEN {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
+
+/// Read `utime + stime` from a `/proc/.../stat` file.
+fn read_stat_ticks(path: &str) -> Option<u64> {
+    let stat = fs::read_to_string(path).ok()?;
+    // The command name may contain spaces, so split after its closing paren.
+    let fields: Vec<&str> = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().collect();
+    // Field 14 (utime) and 15 (stime); the slice starts at field 3.
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// Read resident set size (bytes) and thread count from `/proc/self/status`.
+fn read_status() -> Option<(u64, u64)> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let mut rss_kib = 0;
+    let mut threads = 0;
+    for line in status.lines() {
+        if let Some(value) = line.strip_prefix("VmRSS:") {
+            rss_kib = value.trim().trim_end_matches("kB").trim().parse().unwrap_or(0);
+        } else if let Some(value) = line.strip_prefix("Threads:") {
+            threads = value.trim().parse().unwrap_or(0);
+        }
+    }
+    Some((rss_kib * 1024, threads))
+}
+
+/// Read CPU ticks for every thread under `/proc/self/task`.
+fn read_thread_ticks() -> HashMap<u64, u64> {
+    let Ok(entries) = fs::read_dir("/proc/self/task") else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let tid: u64 = entry.file_name().to_str()?.parse().ok()?;
+            let path = entry.path().join("stat");
+            Some((tid, read_stat_ticks(path.to_str()?)?))
+        })
+        .collect()
+}
+
+/// Return the kernel clock tick rate used by `/proc` CPU times.
+fn clock_ticks_per_sec() -> f64 {
+    #[cfg(unix)]
+    {
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 {
+            return ticks as f64;
+        }
+    }
+    100.0
+}
+
+/// Format a byte count with a binary unit suffix.
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
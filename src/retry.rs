@@ -0,0 +1,82 @@
+//! ==============================================================================
+//! src/retry.rs
+//! Retry policy for task iterations that panic.
+//! ==============================================================================
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+thread_local! {
+    /// Whether the current thread is inside `catch_panic`.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How often an iteration that panics is retried before its task fails.
+///
+/// A retry calls `Task::workload_per_iter` again for the same iteration, so
+/// the task must tolerate being re-entered after a panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetryPolicy {
+    retries: u32,
+    delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Never retry: the first panic fails the task.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Retry a panicking iteration up to `retries` times.
+    pub fn retries(retries: u32) -> Self {
+        Self {
+            retries,
+            ..Self::default()
+        }
+    }
+
+    /// Wait `delay` before each retry.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Return the number of retries allowed per iteration.
+    pub fn max_retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Return the wait before each retry.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+}
+
+/// Run `f`, turning a panic into its message.
+///
+/// While `f` runs, the runtime's panic hook leaves panics on this thread to
+/// the caller instead of ending the run.
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    let outer = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(outer));
+    result.map_err(|payload| panic_message(payload.as_ref()).to_string())
+}
+
+/// Return whether a panic on the current thread will be caught by
+/// `catch_panic`.
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+pub(crate) fn panic_is_caught() -> bool {
+    CATCHING.with(Cell::get)
+}
+
+/// Return the message of a panic payload, as printed by the default hook.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
@@ -0,0 +1,292 @@
+//! ==============================================================================
+//! src/run_report.rs
+//! Outcome of a launch, built from the final client states, and exporters.
+//! ==============================================================================
+
+use std::time::Duration;
+
+use crate::{ClientStore, Phase, TaskId, TaskStatus};
+
+/// Most iteration durations kept per client for percentiles. Past this,
+/// new samples replace random old ones so the set stays representative.
+const MAX_ITERATION_SAMPLES: usize = 4_096;
+
+/// Outcome of every task in a launch.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    /// Time from the launch to the last task finishing, leaving out any time
+    /// the TUI stays open afterwards.
+    pub wall_time: Duration,
+    /// One entry per task: those that started, in start order, then those
+    /// that never did.
+    pub tasks: Vec<TaskReport>,
+}
+
+/// Outcome of a single task.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct TaskReport {
+    /// Client id of the task; `None` if it never started.
+    pub id: Option<TaskId>,
+    /// Label the task reported.
+    pub label: String,
+    /// Named parameters the task reported, such as its point in a `Sweep`.
+    pub params: Vec<(String, f64)>,
+    /// Final status; `None` if the task never started or never reported one.
+    pub status: Option<TaskStatus>,
+    /// Iterations completed, over every phase.
+    pub iterations: u64,
+    /// Iterations the task expected to run over every phase, when known.
+    pub total: Option<u64>,
+    /// Time from the task's start to its last update.
+    pub wall_time: Duration,
+    /// Iteration durations; `None` if no iteration completed.
+    pub iteration_time: Option<IterationStats>,
+    /// Last message of a failed task, usually the error.
+    pub error: Option<String>,
+    /// Phases the task went through, including the last one; empty if it
+    /// reported none.
+    pub phases: Vec<Phase>,
+}
+
+/// Distribution of a task's iteration durations.
+///
+/// Updates that cover several iterations at once count as that many
+/// iterations of the average duration. Percentiles come from a sample of at
+/// most a few thousand updates per task; the mean covers every iteration.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IterationStats {
+    /// Average over every iteration.
+    pub mean: Duration,
+    /// Fastest sampled iteration.
+    pub min: Duration,
+    /// Median.
+    pub p50: Duration,
+    /// 90th percentile.
+    pub p90: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    /// Slowest sampled iteration.
+    pub max: Duration,
+}
+
+impl RunReport {
+    /// Build a report from the final client states of a store.
+    pub(crate) fn new(store: &ClientStore, wall_time: Duration) -> Self {
+        let mut states = store.snapshot();
+        states.sort_by_key(|state| (state.start_time, state.id.0));
+        let tasks = states
+            .into_iter()
+            .map(|mut state| {
+                if let Some(name) = state.phase.take() {
+                    state.phases.push(Phase {
+                        name,
+                        completed: state.current.unwrap_or(0),
+                        total: state.total,
+                        duration: state.last_update.duration_since(state.phase_start),
+                    });
+                }
+                let (iterations, total) = if state.phases.is_empty() {
+                    (state.current.unwrap_or(0), state.total)
+                } else {
+                    (
+                        state.phases.iter().map(|phase| phase.completed).sum(),
+                        state.phases.iter().map(|phase| phase.total).sum(),
+                    )
+                };
+                TaskReport {
+                    id: Some(state.id),
+                    iteration_time: store
+                        .iteration_samples(state.id)
+                        .and_then(IterationSamples::stats),
+                    error: (state.status == Some(TaskStatus::Failed))
+                        .then(|| state.messages.last().cloned())
+                        .flatten(),
+                    label: state.label.unwrap_or_else(|| "unnamed".to_string()),
+                    params: state.params,
+                    status: state.status,
+                    iterations,
+                    total,
+                    wall_time: state.last_update.duration_since(state.start_time),
+                    phases: state.phases,
+                }
+            })
+            .collect();
+        Self { wall_time, tasks }
+    }
+
+    /// Return whether every task completed.
+    ///
+    /// Failed, canceled, and never-started tasks all count against it, so
+    /// this is the natural exit status of a CI job:
+    /// `std::process::exit(if report.all_succeeded() { 0 } else { 1 })`.
+    pub fn all_succeeded(&self) -> bool {
+        self.tasks.iter().all(TaskReport::succeeded)
+    }
+
+    /// Return the tasks that failed, in report order.
+    pub fn failed(&self) -> impl Iterator<Item = &TaskReport> {
+        self.with_status(TaskStatus::Failed)
+    }
+
+    /// Return the tasks that were canceled, in report order.
+    pub fn canceled(&self) -> impl Iterator<Item = &TaskReport> {
+        self.with_status(TaskStatus::Canceled)
+    }
+
+    /// Return the tasks that never started, in report order.
+    pub fn not_started(&self) -> impl Iterator<Item = &TaskReport> {
+        self.tasks.iter().filter(|task| task.status.is_none())
+    }
+
+    fn with_status(&self, status: TaskStatus) -> impl Iterator<Item = &TaskReport> {
+        self.tasks
+            .iter()
+            .filter(move |task| task.status == Some(status))
+    }
+}
+
+impl TaskReport {
+    /// Report a task that was still queued when the launch ended.
+    pub(crate) fn not_started(
+        label: impl Into<String>,
+        params: Vec<(String, f64)>,
+        total: u64,
+    ) -> Self {
+        Self {
+            id: None,
+            label: label.into(),
+            params,
+            status: None,
+            iterations: 0,
+            total: Some(total),
+            wall_time: Duration::ZERO,
+            iteration_time: None,
+            error: None,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Return whether the task completed.
+    pub fn succeeded(&self) -> bool {
+        self.status == Some(TaskStatus::Completed)
+    }
+}
+
+/// Iteration durations observed by the store for one client.
+#[derive(Debug, Default)]
+pub(crate) struct IterationSamples {
+    /// Total time of every observed iteration.
+    elapsed: Duration,
+    iterations: u64,
+    /// Average iteration duration of an update and the iterations it covers.
+    samples: Vec<(Duration, u64)>,
+    /// Updates offered to `samples`, including replaced ones.
+    seen: u64,
+}
+
+impl IterationSamples {
+    /// Record `steps` iterations that took `elapsed` in total.
+    pub(crate) fn record(&mut self, elapsed: Duration, steps: u64) {
+        self.elapsed = self.elapsed.saturating_add(elapsed);
+        self.iterations = self.iterations.saturating_add(steps);
+        self.seen += 1;
+        let sample = (elapsed / u32::try_from(steps).unwrap_or(u32::MAX), steps);
+        if self.samples.len() < MAX_ITERATION_SAMPLES {
+            self.samples.push(sample);
+        } else if let Ok(slot) = usize::try_from(rand::random_range(0..self.seen)) {
+            if let Some(replaced) = self.samples.get_mut(slot) {
+                *replaced = sample;
+            }
+        }
+    }
+
+    fn stats(&self) -> Option<IterationStats> {
+        let mut samples = self.samples.clone();
+        samples.sort_unstable_by_key(|(duration, _)| *duration);
+        let (min, _) = *samples.first()?;
+        let (max, _) = *samples.last()?;
+        let weight: u64 = samples.iter().map(|(_, steps)| steps).sum();
+        let percentile = |q: f64| {
+            let rank = ((weight as f64 * q).ceil() as u64).max(1);
+            let mut covered = 0;
+            samples
+                .iter()
+                .find(|(_, steps)| {
+                    covered += steps;
+                    covered >= rank
+                })
+                .map_or(max, |(duration, _)| *duration)
+        };
+        Some(IterationStats {
+            mean: Duration::from_secs_f64(self.elapsed.as_secs_f64() / self.iterations as f64),
+            min,
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p99: percentile(0.99),
+            max,
+        })
+    }
+}
+
+/// Receives the report once a launch finishes, for example to write it to a
+/// file or send it elsewhere.
+///
+/// Any `FnMut(&RunReport) -> Result<(), _>` closure is an exporter.
+pub trait Exporter {
+    /// Export the finished run.
+    fn export(
+        &mut self,
+        report: &RunReport,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl<F> Exporter for F
+where
+    F: FnMut(&RunReport) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn export(
+        &mut self,
+        report: &RunReport,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_weigh_samples_by_their_iterations() {
+        let mut samples = IterationSamples::default();
+        for _ in 0..3 {
+            samples.record(Duration::from_millis(1), 1);
+        }
+        samples.record(Duration::from_millis(90), 9);
+
+        let stats = samples.stats().expect("samples were recorded");
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.p50, Duration::from_millis(10));
+        assert_eq!(stats.p90, Duration::from_millis(10));
+        assert_eq!(stats.max, Duration::from_millis(10));
+        assert_eq!(stats.mean, Duration::from_micros(7_750));
+    }
+
+    #[test]
+    fn samples_past_the_limit_replace_old_ones() {
+        let mut samples = IterationSamples::default();
+        let updates = MAX_ITERATION_SAMPLES as u64 + 100;
+        for _ in 0..updates {
+            samples.record(Duration::from_millis(2), 2);
+        }
+
+        assert_eq!(samples.samples.len(), MAX_ITERATION_SAMPLES);
+        assert_eq!(samples.seen, updates);
+        assert_eq!(samples.iterations, 2 * updates);
+        let stats = samples.stats().expect("samples were recorded");
+        assert_eq!(stats.mean, Duration::from_millis(1));
+    }
+}
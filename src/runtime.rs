@@ -1,24 +1,65 @@
-/// ==============================================================================
-/// src/runtime.rs
-/// Runtime thread for draining the store and rendering a TUI.
-/// ==============================================================================
+//! ==============================================================================
+//! src/runtime.rs
+//! Runtime thread for draining the store and rendering a TUI.
+//! ==============================================================================
 
 #[cfg(feature = "tui")]
-use std::io;
+use std::collections::HashSet;
+#[cfg(feature = "tui")]
+use std::fs::File;
+#[cfg(feature = "tui")]
+use std::io::{self, Write};
 #[cfg(feature = "tui")]
 use std::time::{Duration, Instant};
 
 #[cfg(feature = "tui")]
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 #[cfg(feature = "tui")]
-use ratatui::{prelude::*, widgets::{Block, Borders, List, ListItem, Paragraph}};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Clear, Gauge, List, ListItem, Paragraph, Sparkline},
+    TerminalOptions, Viewport,
+};
 
 #[cfg(feature = "tui")]
-use crate::ClientStore;
+use crate::client_table::{format_metric, render_clients, ClientsView};
+#[cfg(feature = "tui")]
+use crate::key_map::{Action, Key, KeyMap};
+#[cfg(feature = "tui")]
+use crate::layout::{ClientColumns, DashboardLayout, Panel};
+#[cfg(feature = "tui")]
+use crate::resources::{format_bytes, ResourceSampler};
+#[cfg(feature = "tui")]
+use crate::summary::RunSummary;
+#[cfg(feature = "tui")]
+use crate::sweep::format_param;
+#[cfg(feature = "tui")]
+use crate::terminal_guard::TerminalGuard;
+#[cfg(feature = "tui")]
+use crate::theme::Theme;
+#[cfg(feature = "tui")]
+use crate::{ClientStore, TaskId, TaskStatus};
+#[cfg(all(feature = "tui", unix))]
+use crate::output_capture::OutputCapture;
+
+/// What the runtime does once every client has finished.
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EndBehavior {
+    /// Keep showing the dashboard until the user quits.
+    #[default]
+    Wait,
+    /// Leave once every client has finished, after showing the final state
+    /// for `linger`.
+    Exit { linger: Duration },
+    /// Show a summary screen until any key is pressed, then print the same
+    /// summary to the normal terminal.
+    Summary,
+}
 
 /// Runtime that owns the main loop for draining updates and rendering.
 ///
@@ -34,8 +75,50 @@ pub struct Runtime {
     project_label: Option<String>,
     /// Monotonic start time for the runtime session.
     start_time: Option<Instant>,
-    /// Whether a quit confirmation is currently active.
-    confirm_quit: bool,
+    /// Yes/no question awaiting an answer, if any.
+    prompt: Option<Prompt>,
+    /// Whether to redirect stdout/stderr into the logs pane while running.
+    capture_output: bool,
+    /// Active stdout/stderr redirection, if any.
+    #[cfg(unix)]
+    capture: Option<OutputCapture>,
+    /// Captured stdout/stderr lines shown in the output pane.
+    output: Vec<String>,
+    /// How many lines the output pane is scrolled up from the bottom.
+    output_scroll: usize,
+    /// Process resource sampler backing the resources panel, if enabled.
+    resources: Option<ResourceSampler>,
+    /// Key bindings for all user actions.
+    key_map: KeyMap,
+    /// Whether the help overlay is shown.
+    show_help: bool,
+    /// Styles for every TUI element.
+    theme: Theme,
+    /// Which panels are drawn, in what order, and how tall.
+    layout: DashboardLayout,
+    /// Columns of the clients table.
+    columns: ClientColumns,
+    /// Index of the selected client in display order.
+    selected: usize,
+    /// Parameter the clients are sorted by, or `None` for start order.
+    sort_by: Option<String>,
+    /// What to do once every client has finished.
+    end_behavior: EndBehavior,
+    /// Number of clients to wait for before the run can count as finished.
+    expected_clients: usize,
+    /// When every client was first seen finished.
+    finished_at: Option<Instant>,
+    /// Final summary, once shown by `EndBehavior::Summary`.
+    summary: Option<RunSummary>,
+    /// Id, label, and whether it has finished, for the client at `selected`
+    /// as of the last frame.
+    selected_client: Option<(TaskId, String, bool)>,
+    /// Height of the inline viewport, or `None` for the alternate screen.
+    inline: Option<u16>,
+    /// Clients already printed above the inline viewport.
+    printed_clients: HashSet<TaskId>,
+    /// Global messages already printed above the inline viewport.
+    printed_logs: u64,
 }
 
 #[cfg(feature = "tui")]
@@ -47,7 +130,28 @@ impl Runtime {
             quit_on_q: true,
             project_label: None,
             start_time: None,
-            confirm_quit: false,
+            prompt: None,
+            capture_output: false,
+            #[cfg(unix)]
+            capture: None,
+            output: Vec::new(),
+            output_scroll: 0,
+            resources: None,
+            key_map: KeyMap::default(),
+            show_help: false,
+            theme: Theme::default(),
+            layout: DashboardLayout::default(),
+            columns: ClientColumns::default(),
+            selected: 0,
+            sort_by: None,
+            selected_client: None,
+            end_behavior: EndBehavior::default(),
+            expected_clients: 1,
+            finished_at: None,
+            summary: None,
+            inline: None,
+            printed_clients: HashSet::new(),
+            printed_logs: 0,
         }
     }
 
@@ -63,193 +167,1105 @@ impl Runtime {
         self
     }
 
+    /// Redirect stdout and stderr into a scrollback pane while the TUI is active.
+    ///
+    /// Stray `println!`s and third-party output would otherwise tear up the
+    /// alternate screen. The full output is replayed to the real terminal
+    /// after the TUI exits. Only supported on Unix; elsewhere this is a no-op.
+    pub fn capture_output(mut self, enabled: bool) -> Self {
+        self.capture_output = enabled;
+        self
+    }
+
+    /// Set the color theme, for example `Theme::colorblind()`.
+    ///
+    /// The default theme honors `NO_COLOR`; an explicit theme is used as is.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Replace the key bindings, for example with `KeyMap::load(path)?`.
+    pub fn key_map(mut self, key_map: KeyMap) -> Self {
+        self.key_map = key_map;
+        self
+    }
+
+    /// Replace the panel layout, for example to reorder panels, change their
+    /// heights, or start with the detail panel visible.
+    pub fn layout(mut self, layout: DashboardLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Choose, order, and size the columns of the clients table.
+    pub fn columns(mut self, columns: ClientColumns) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Sort clients by the parameter `name`, as set by
+    /// `ClientHandle::set_params` or a `Sweep`, instead of by start time.
+    ///
+    /// Clients without that parameter come last. The sort key cycles through
+    /// every parameter name at runtime.
+    pub fn sort_by_param(mut self, name: impl Into<String>) -> Self {
+        self.sort_by = Some(name.into());
+        self
+    }
+
+    /// Choose what happens once every client has finished.
+    ///
+    /// The default, `EndBehavior::Wait`, keeps the dashboard open until the
+    /// user quits.
+    pub fn on_finish(mut self, behavior: EndBehavior) -> Self {
+        self.end_behavior = behavior;
+        self
+    }
+
+    /// Treat the run as finished only once at least `count` clients have
+    /// reported, so an early finisher does not end it before the others start.
+    ///
+    /// The launchers set this to the number of tasks.
+    pub fn expect_clients(mut self, count: usize) -> Self {
+        self.expected_clients = count.max(1);
+        self
+    }
+
+    /// Draw in a region `height` rows tall at the bottom of the terminal
+    /// instead of taking over the screen.
+    ///
+    /// The region lists unfinished clients and the controls. A line for each
+    /// client that finishes and each global log message is printed above it,
+    /// where it stays in the scrollback after the run. The dashboard layout
+    /// does not apply, and `EndBehavior::Summary` prints its summary without
+    /// showing a summary screen first.
+    ///
+    /// The viewport locates itself by querying the terminal through stdout,
+    /// so `capture_output` is ignored in this mode; report messages with
+    /// `ClientReporter::log` instead of printing them.
+    pub fn inline(mut self, height: u16) -> Self {
+        self.inline = Some(height);
+        self
+    }
+
+    /// Show a panel with process CPU, RSS, and thread count sampled from
+    /// `/proc` once per second.
+    ///
+    /// Clients whose worker thread is known also show that thread's CPU usage.
+    /// Only Linux provides these figures; elsewhere the panel stays empty.
+    pub fn monitor_resources(mut self, enabled: bool) -> Self {
+        self.resources = enabled.then(|| ResourceSampler::new(RESOURCE_SAMPLE_INTERVAL));
+        self
+    }
+
     /// Run the runtime loop, draining the store and drawing to the terminal.
     ///
-    /// This method blocks until the loop exits (for example, when `q` is pressed).
+    /// This method blocks until the loop exits: when the user quits, which
+    /// asks every client to cancel, or as configured by `on_finish`.
+    ///
+    /// Ctrl-C, SIGINT, SIGTERM, or a panic on any thread also end the loop:
+    /// every client is asked to cancel, the terminal is restored, recorded
+    /// panic messages are printed, and an error is returned.
     pub fn run(&mut self, store: &mut ClientStore) -> io::Result<()> {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+        let out = self.start_capture()?;
+        let mut terminal = enter_terminal(out, self.inline)?;
+        let guard = TerminalGuard::install();
+        let frame_time = self.begin_session();
 
-        let frame_time = Duration::from_millis(1_000 / self.fps.max(1));
+        let outcome = loop {
+            let frame_start = Instant::now();
+
+            match self.guarded_frame(&guard, &mut terminal, store) {
+                Ok(false) => {}
+                Ok(true) => break Ok(()),
+                Err(err) => break Err(err),
+            }
+
+            let elapsed = frame_start.elapsed();
+            if elapsed < frame_time {
+                std::thread::sleep(frame_time - elapsed);
+            }
+        };
+
+        self.end_session(&mut terminal, store, guard, outcome)
+    }
+
+    /// Run the runtime loop inside an async context.
+    ///
+    /// Frames are paced with `tokio::time` instead of sleeping the thread, so
+    /// other tasks on the same executor keep making progress between frames.
+    #[cfg(feature = "tokio")]
+    pub async fn run_async(&mut self, store: &mut ClientStore) -> io::Result<()> {
+        let out = self.start_capture()?;
+        let mut terminal = enter_terminal(out, self.inline)?;
+        let guard = TerminalGuard::install();
+        let frame_time = self.begin_session();
+        let mut ticker = tokio::time::interval(frame_time);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let outcome = loop {
+            ticker.tick().await;
+
+            match self.guarded_frame(&guard, &mut terminal, store) {
+                Ok(false) => {}
+                Ok(true) => break Ok(()),
+                Err(err) => break Err(err),
+            }
+        };
+
+        self.end_session(&mut terminal, store, guard, outcome)
+    }
+
+    /// Begin capturing stdout/stderr if enabled, returning where to draw.
+    fn start_capture(&mut self) -> io::Result<TermOut> {
+        #[cfg(unix)]
+        if self.capture_output && self.inline.is_none() {
+            let (capture, terminal) = OutputCapture::start()?;
+            self.capture = Some(capture);
+            return Ok(TermOut::File(terminal));
+        }
+        Ok(TermOut::Stdout(io::stdout()))
+    }
+
+    /// Restore stdout/stderr if they were captured and replay their output.
+    fn stop_capture(&mut self) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Some(capture) = self.capture.take() {
+            return capture.replay();
+        }
+        Ok(())
+    }
+
+    /// Restore the terminal, replay captured output, and print the summary
+    /// if one was requested, followed by any panics recorded by `guard`.
+    ///
+    /// When the loop ended with an error, every client is asked to cancel
+    /// and that error is returned once the terminal is restored.
+    fn end_session(
+        &mut self,
+        terminal: &mut TuiTerminal,
+        store: &mut ClientStore,
+        guard: TerminalGuard,
+        outcome: io::Result<()>,
+    ) -> io::Result<()> {
+        if outcome.is_err() {
+            store.cancel_all();
+        }
+        let restored = if self.inline.is_some() {
+            self.finish_inline(terminal, store)
+                .and_then(|()| leave_terminal(terminal, true))
+        } else {
+            leave_terminal(terminal, false).and_then(|()| self.stop_capture())
+        };
+        let panics = guard.finish();
+        restored?;
+        self.print_summary(store)?;
+        for panic in panics {
+            eprintln!("{panic}");
+        }
+        outcome
+    }
+
+    /// Print what is left above the inline viewport, including a line for
+    /// every client that has not finished, then clear the viewport.
+    fn finish_inline(
+        &mut self,
+        terminal: &mut TuiTerminal,
+        store: &mut ClientStore,
+    ) -> io::Result<()> {
+        store.drain();
+        let snapshot = display_order(store.snapshot(), self.sort_by.as_deref());
+        self.print_above(terminal, store, &snapshot, true)?;
+        terminal.clear()
+    }
+
+    /// Print new global messages and a line for each newly finished client
+    /// above the inline viewport.
+    ///
+    /// With `all`, unfinished clients get a line too.
+    fn print_above(
+        &mut self,
+        terminal: &mut TuiTerminal,
+        store: &ClientStore,
+        snapshot: &[crate::ClientState],
+        all: bool,
+    ) -> io::Result<()> {
+        let logs = store.logs();
+        let fresh = (store.log_count() - self.printed_logs).min(logs.len() as u64) as usize;
+        self.printed_logs = store.log_count();
+        let mut lines: Vec<Line<'static>> = logs
+            .skip(store.logs().len() - fresh)
+            .map(|message| Line::styled(message.to_string(), self.theme.dim))
+            .collect();
+
+        for state in snapshot {
+            let finished = state.status.is_some_and(TaskStatus::is_terminal);
+            if (finished || all) && self.printed_clients.insert(state.id) {
+                lines.push(client_line(&self.theme, state));
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok(());
+        }
+        let height = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+        terminal.insert_before(height, |buf| {
+            Paragraph::new(lines).render(buf.area, buf);
+        })
+    }
+
+    /// Print the final summary when `EndBehavior::Summary` is configured.
+    fn print_summary(&mut self, store: &mut ClientStore) -> io::Result<()> {
+        if self.end_behavior == EndBehavior::Summary {
+            let summary = match self.summary.take() {
+                Some(summary) => summary,
+                // The run ended before every client finished.
+                None => {
+                    store.drain();
+                    RunSummary::new(
+                        &display_order(store.snapshot(), self.sort_by.as_deref()),
+                        self.session_elapsed(),
+                    )
+                }
+            };
+            let mut out = io::stdout().lock();
+            out.write_all(summary.to_text().as_bytes())?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Return the time since the session started.
+    fn session_elapsed(&self) -> Duration {
+        self.start_time
+            .map(|start| start.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0))
+    }
+
+    /// Record the session start time and return the target frame duration.
+    fn begin_session(&mut self) -> Duration {
         if self.start_time.is_none() {
             self.start_time = Some(Instant::now());
         }
+        Duration::from_millis(1_000 / self.fps.max(1))
+    }
 
-        loop {
-            let frame_start = Instant::now();
+    /// Check for interruptions, then draw a frame with panics turned into
+    /// errors.
+    fn guarded_frame(
+        &mut self,
+        guard: &TerminalGuard,
+        terminal: &mut TuiTerminal,
+        store: &mut ClientStore,
+    ) -> io::Result<bool> {
+        guard.status()?;
+        guard.frame(|| self.frame(guard, terminal, store))
+    }
 
-            if self.quit_on_q {
-                if let Some(quit) = handle_quit_input(self.confirm_quit)? {
-                    match quit {
-                        QuitAction::RequestConfirm => self.confirm_quit = true,
-                        QuitAction::Cancel => self.confirm_quit = false,
-                        QuitAction::Confirm => break,
+    /// Handle input, drain the store, and draw a single frame.
+    ///
+    /// Returns `true` when the loop should end. This never blocks,
+    /// which keeps it usable from both the sync and async loops.
+    fn frame(
+        &mut self,
+        guard: &TerminalGuard,
+        terminal: &mut TuiTerminal,
+        store: &mut ClientStore,
+    ) -> io::Result<bool> {
+        if let Some(key) = read_key()? {
+            let action = self.key_map.action_for(&key, self.prompt.is_some());
+            if action.is_none() && CTRL_C.matches(&key) {
+                // Raw mode delivers Ctrl-C as a key instead of SIGINT.
+                guard.interrupt();
+                return Ok(false);
+            } else if self.summary.is_some() {
+                // Any key dismisses the summary screen and ends the run.
+                return Ok(true);
+            } else if self.show_help {
+                // Any key dismisses the help overlay.
+                self.show_help = false;
+            } else if let Some(action) = action {
+                if self.handle_action(action, store) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        store.drain();
+        #[cfg(unix)]
+        if let Some(capture) = &self.capture {
+            let lines = capture.take_lines();
+            if self.output_scroll > 0 {
+                self.output_scroll += lines.len();
+            }
+            self.output.extend(lines);
+            let excess = self.output.len().saturating_sub(MAX_OUTPUT_LINES);
+            self.output.drain(..excess);
+        }
+        if let Some(resources) = &mut self.resources {
+            resources.sample();
+        }
+        let mut snapshot = display_order(store.snapshot(), self.sort_by.as_deref());
+        if self.inline.is_some() {
+            self.print_above(terminal, store, &snapshot, false)?;
+        }
+        let elapsed = self.session_elapsed();
+        if self.summary.is_none() && self.all_finished(&snapshot) {
+            match self.end_behavior {
+                EndBehavior::Wait => {}
+                EndBehavior::Exit { linger } => {
+                    let finished_at = *self.finished_at.get_or_insert_with(Instant::now);
+                    if finished_at.elapsed() >= linger {
+                        return Ok(true);
+                    }
+                }
+                EndBehavior::Summary => {
+                    self.summary = Some(RunSummary::new(&snapshot, elapsed));
+                    if self.inline.is_some() {
+                        // The inline viewport is too small for a summary screen.
+                        return Ok(true);
                     }
                 }
             }
+        }
+        if self.inline.is_some() {
+            // Finished clients have been printed above the viewport.
+            snapshot.retain(|state| !state.status.is_some_and(TaskStatus::is_terminal));
+        }
+        self.selected = self.selected.min(snapshot.len().saturating_sub(1));
+        self.selected_client = snapshot.get(self.selected).map(|state| {
+            let label = state.label.clone().unwrap_or_else(|| "unnamed".to_string());
+            let finished = state.status.is_some_and(TaskStatus::is_terminal);
+            (state.id, label, finished)
+        });
+        let logs: Vec<&str> = store.logs().collect();
+        let header = format_project_header(self.project_label.as_deref(), elapsed);
 
-            store.drain();
-            let snapshot = store.snapshot();
-            let elapsed = self
-                .start_time
-                .map(|start| start.elapsed())
-                .unwrap_or_else(|| Duration::from_secs(0));
-            let header = format_project_header(self.project_label.as_deref(), elapsed);
+        let view = FrameView {
+            snapshot: &snapshot,
+            logs: &logs,
+            output: self.capture_active().then_some((&self.output[..], self.output_scroll)),
+            header: &header,
+            elapsed,
+            prompt: self.prompt.as_ref(),
+            resources: self.resources.as_ref(),
+            key_map: &self.key_map,
+            show_help: self.show_help,
+            theme: &self.theme,
+            layout: &self.layout,
+            columns: &self.columns,
+            selected: self.selected,
+            sort_by: self.sort_by.as_deref(),
+            summary: self.summary.as_ref(),
+            inline: self.inline.is_some(),
+        };
+        terminal.draw(|f| render_frame(f, &view))?;
+        Ok(false)
+    }
 
-            terminal.draw(|f| render_frame(f, &snapshot, &header, self.confirm_quit))?;
+    /// Return whether the expected clients have all reported and finished.
+    fn all_finished(&self, snapshot: &[crate::ClientState]) -> bool {
+        snapshot.len() >= self.expected_clients
+            && snapshot
+                .iter()
+                .all(|state| state.status.is_some_and(TaskStatus::is_terminal))
+    }
 
-            let elapsed = frame_start.elapsed();
-            if elapsed < frame_time {
-                std::thread::sleep(frame_time - elapsed);
+    /// Apply a key map action. Returns `true` when quitting is confirmed.
+    fn handle_action(&mut self, action: Action, store: &ClientStore) -> bool {
+        let selected_id = self.selected_client.as_ref().map(|(id, _, _)| *id);
+        match action {
+            Action::Quit if self.quit_on_q => self.prompt = Some(Prompt::Quit),
+            Action::Cancel => {
+                if let Some((id, label, false)) = &self.selected_client {
+                    self.prompt = Some(Prompt::Cancel {
+                        id: *id,
+                        label: label.clone(),
+                    });
+                }
+            }
+            Action::Confirm => match self.prompt.take() {
+                Some(Prompt::Quit) => {
+                    store.cancel_all();
+                    return true;
+                }
+                Some(Prompt::Cancel { id, .. }) => {
+                    store.cancel(id);
+                }
+                None => {}
+            },
+            Action::Dismiss => self.prompt = None,
+            Action::ScrollUp => {
+                self.output_scroll = (self.output_scroll + OUTPUT_SCROLL_STEP)
+                    .min(self.output.len().saturating_sub(1));
+            }
+            Action::ScrollDown => {
+                self.output_scroll = self.output_scroll.saturating_sub(OUTPUT_SCROLL_STEP);
+            }
+            Action::ScrollBottom => self.output_scroll = 0,
+            Action::SelectPrev => self.selected = self.selected.saturating_sub(1),
+            Action::SelectNext => self.selected = self.selected.saturating_add(1),
+            Action::Pause => {
+                if let Some(id) = selected_id {
+                    store.pause(id);
+                }
+            }
+            Action::Resume => {
+                if let Some(id) = selected_id {
+                    store.resume(id);
+                }
+            }
+            Action::PauseAll => store.pause_all(),
+            Action::ResumeAll => store.resume_all(),
+            Action::ToggleSummary => self.layout.toggle(Panel::Summary),
+            Action::ToggleDetail => self.layout.toggle(Panel::Detail),
+            Action::ToggleResources => {
+                if self.resources.is_none() {
+                    self.resources = Some(ResourceSampler::new(RESOURCE_SAMPLE_INTERVAL));
+                    self.layout.set_visible(Panel::Resources, true);
+                } else {
+                    self.layout.toggle(Panel::Resources);
+                }
+            }
+            Action::ToggleOutput => self.layout.toggle(Panel::Output),
+            Action::ToggleLogs => self.layout.toggle(Panel::Logs),
+            Action::CycleSort => self.cycle_sort(store),
+            Action::Help => self.show_help = true,
+            Action::Quit => {}
+        }
+        false
+    }
+
+    /// Sort by the parameter after the current one, in the order clients
+    /// first report them, then go back to start order. The selection stays
+    /// on the same client.
+    fn cycle_sort(&mut self, store: &ClientStore) {
+        let mut names: Vec<String> = Vec::new();
+        for state in display_order(store.snapshot(), None) {
+            for (name, _) in state.params {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        self.sort_by = match &self.sort_by {
+            None => names.into_iter().next(),
+            Some(current) => names
+                .iter()
+                .position(|name| name == current)
+                .and_then(|i| names.get(i + 1).cloned()),
+        };
+        if let Some((id, _, _)) = &self.selected_client {
+            let order = display_order(store.snapshot(), self.sort_by.as_deref());
+            if let Some(i) = order.iter().position(|state| state.id == *id) {
+                self.selected = i;
             }
         }
+    }
+
+    /// Return whether stdout/stderr are currently being captured.
+    fn capture_active(&self) -> bool {
+        #[cfg(unix)]
+        {
+            self.capture.is_some()
+        }
+        #[cfg(not(unix))]
+        {
+            false
+        }
+    }
+}
+
+/// Sort a snapshot for display; the store itself is unordered, so rows are
+/// kept stable by start time.
+///
+/// With `sort_by`, clients are ordered by that parameter instead, so equal
+/// values end up next to each other in start order and clients without it
+/// come last.
+#[cfg(feature = "tui")]
+fn display_order(
+    mut snapshot: Vec<crate::ClientState>,
+    sort_by: Option<&str>,
+) -> Vec<crate::ClientState> {
+    snapshot.sort_by_key(|state| (state.start_time, state.id.0));
+    if let Some(name) = sort_by {
+        let param = |state: &crate::ClientState| {
+            state
+                .params
+                .iter()
+                .find(|(existing, _)| existing == name)
+                .map(|(_, value)| *value)
+        };
+        snapshot.sort_by(|a, b| match (param(a), param(b)) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+    }
+    snapshot
+}
+
+/// A yes/no question shown in the controls panel.
+#[cfg(feature = "tui")]
+enum Prompt {
+    /// Leave the runtime.
+    Quit,
+    /// Cancel one client; the rest keep running.
+    Cancel { id: TaskId, label: String },
+}
+
+/// Maximum number of captured output lines kept for scrollback.
+#[cfg(feature = "tui")]
+const MAX_OUTPUT_LINES: usize = 10_000;
+
+/// How often the resources panel re-reads `/proc`.
+#[cfg(feature = "tui")]
+const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Narrowest resources panel that still gets CPU and RSS sparklines.
+#[cfg(feature = "tui")]
+const MIN_SPARKLINE_WIDTH: u16 = 50;
+
+/// Shortest inline viewport that also shows the controls panel.
+#[cfg(feature = "tui")]
+const MIN_INLINE_CONTROLS_HEIGHT: u16 = 8;
+
+/// Key that interrupts the run like SIGINT, unless the key map binds it.
+#[cfg(feature = "tui")]
+const CTRL_C: Key = Key::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+/// Lines moved per scroll key press in the output pane.
+#[cfg(feature = "tui")]
+const OUTPUT_SCROLL_STEP: usize = 5;
+
+#[cfg(feature = "tui")]
+type TuiTerminal = Terminal<CrosstermBackend<TermOut>>;
+
+/// Destination the TUI draws on.
+///
+/// This is the process stdout, or a duplicate of the original stdout while
+/// output is being captured.
+#[cfg(feature = "tui")]
+enum TermOut {
+    Stdout(io::Stdout),
+    #[cfg_attr(not(unix), allow(dead_code))]
+    File(File),
+}
+
+#[cfg(feature = "tui")]
+impl Write for TermOut {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            TermOut::Stdout(out) => out.write(buf),
+            TermOut::File(out) => out.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            TermOut::Stdout(out) => out.flush(),
+            TermOut::File(out) => out.flush(),
+        }
+    }
+}
 
-        disable_raw_mode()?;
+/// Switch the terminal into raw mode and either the alternate screen or an
+/// inline viewport `inline` rows tall.
+#[cfg(feature = "tui")]
+fn enter_terminal(mut out: TermOut, inline: Option<u16>) -> io::Result<TuiTerminal> {
+    enable_raw_mode()?;
+    let viewport = match inline {
+        Some(height) => Viewport::Inline(height),
+        None => {
+            execute!(out, EnterAlternateScreen)?;
+            Viewport::Fullscreen
+        }
+    };
+    let backend = CrosstermBackend::new(out);
+    Terminal::with_options(backend, TerminalOptions { viewport })
+}
+
+/// Restore the terminal to its normal state.
+#[cfg(feature = "tui")]
+fn leave_terminal(terminal: &mut TuiTerminal, inline: bool) -> io::Result<()> {
+    disable_raw_mode()?;
+    if !inline {
         execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-        terminal.show_cursor()?;
-        std::process::exit(0);
     }
+    terminal.show_cursor()
 }
 
+/// One line recording how a client ended, printed above the inline viewport.
 #[cfg(feature = "tui")]
-enum QuitAction {
-    RequestConfirm,
-    Confirm,
-    Cancel,
+fn client_line(theme: &Theme, state: &crate::ClientState) -> Line<'static> {
+    let status = state
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let total = state
+        .total
+        .map(|t| t.to_string())
+        .unwrap_or_else(|| "?".to_string());
+    let mut text = format!(
+        " {} ({}/{total}, {})",
+        state.label.as_deref().unwrap_or("unnamed"),
+        state.current.unwrap_or(0),
+        format_duration(state.last_update.duration_since(state.start_time))
+    );
+    if state.status == Some(TaskStatus::Failed) {
+        if let Some(message) = state.messages.last() {
+            text.push_str(&format!(": {message}"));
+        }
+    }
+    Line::from(vec![
+        Span::styled(format!("{status:>9}"), theme.status(state.status)),
+        Span::raw(text),
+    ])
 }
 
-fn handle_quit_input(confirming: bool) -> io::Result<Option<QuitAction>> {
+/// Read at most one pending key press without blocking.
+#[cfg(feature = "tui")]
+fn read_key() -> io::Result<Option<KeyEvent>> {
     if event::poll(Duration::from_millis(0))? {
         if let Event::Key(key) = event::read()? {
-            return Ok(match key.code {
-                KeyCode::Char('q') if !confirming => Some(QuitAction::RequestConfirm),
-                KeyCode::Char('y') if confirming => Some(QuitAction::Confirm),
-                KeyCode::Char('n') if confirming => Some(QuitAction::Cancel),
-                KeyCode::Esc if confirming => Some(QuitAction::Cancel),
-                _ => None,
-            });
+            if key.kind == KeyEventKind::Press {
+                return Ok(Some(key));
+            }
         }
     }
     Ok(None)
 }
 
+/// Everything `render_frame` needs to draw one frame.
 #[cfg(feature = "tui")]
-fn render_frame(
-    frame: &mut Frame<'_>,
-    snapshot: &[crate::ClientState],
-    header: &str,
-    confirm_quit: bool,
-) {
+struct FrameView<'a> {
+    snapshot: &'a [crate::ClientState],
+    logs: &'a [&'a str],
+    /// Captured output lines and scroll offset, when capture is active.
+    output: Option<(&'a [String], usize)>,
+    header: &'a str,
+    /// Time since the session started, driving animations.
+    elapsed: Duration,
+    prompt: Option<&'a Prompt>,
+    resources: Option<&'a ResourceSampler>,
+    key_map: &'a KeyMap,
+    show_help: bool,
+    theme: &'a Theme,
+    layout: &'a DashboardLayout,
+    columns: &'a ClientColumns,
+    selected: usize,
+    /// Parameter the clients are sorted by, if any.
+    sort_by: Option<&'a str>,
+    summary: Option<&'a RunSummary>,
+    /// Whether to draw the compact inline viewport instead of the layout.
+    inline: bool,
+}
+
+#[cfg(feature = "tui")]
+fn render_frame(frame: &mut Frame<'_>, view: &FrameView<'_>) {
+    let theme = view.theme;
     let size = frame.area();
 
-    let blocks = Layout::default()
+    // Panels without content give their space to the others.
+    let panels: Vec<(Panel, Constraint)> = if view.inline {
+        inline_panels(size.height).collect()
+    } else {
+        view.layout.visible().collect()
+    };
+    let panels: Vec<(Panel, Constraint)> = panels
+        .into_iter()
+        .filter(|(panel, _)| match panel {
+            Panel::Resources => view.resources.is_some(),
+            Panel::Output => view.output.is_some(),
+            Panel::Logs => !view.logs.is_empty(),
+            _ => true,
+        })
+        .collect();
+    let areas = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Percentage(67),
-            Constraint::Percentage(30),
-        ])
+        .constraints(panels.iter().map(|(_, size)| *size))
         .split(size);
 
-    let items: Vec<ListItem> = snapshot
-        .iter()
-        .map(|state| format_task_item(state))
-        .collect();
+    for ((panel, _), area) in panels.into_iter().zip(areas.iter().copied()) {
+        match panel {
+            Panel::Header => {
+                let header = Paragraph::new(view.header.to_string())
+                    .block(theme.block("Project"))
+                    .alignment(Alignment::Center);
+                frame.render_widget(header, area);
+            }
+            Panel::Summary => render_summary(frame, view.snapshot, theme, area),
+            Panel::Resources => {
+                if let Some(resources) = view.resources {
+                    render_resources(frame, theme, resources, area);
+                }
+            }
+            Panel::Clients => {
+                let clients = ClientsView {
+                    snapshot: view.snapshot,
+                    columns: view.columns,
+                    thread_cpu: view.resources.map(ResourceSampler::thread_cpu),
+                    selected: (!view.snapshot.is_empty()).then_some(view.selected),
+                    sort_by: view.sort_by,
+                    theme,
+                    elapsed: view.elapsed,
+                };
+                render_clients(frame, area, &clients);
+            }
+            Panel::Detail => {
+                let detail = render_detail(theme, view.snapshot.get(view.selected), area.height);
+                frame.render_widget(detail, area);
+            }
+            Panel::Output => {
+                if let Some((output, scroll)) = view.output {
+                    let title = "Output (PgUp/PgDn/End)";
+                    frame.render_widget(
+                        render_tail(theme, title, output, scroll, area.height),
+                        area,
+                    );
+                }
+            }
+            Panel::Logs => {
+                let logs: Vec<String> = view.logs.iter().map(|line| line.to_string()).collect();
+                frame.render_widget(render_tail(theme, "Logs", &logs, 0, area.height), area);
+            }
+            Panel::Controls => frame.render_widget(render_controls(view), area),
+        }
+    }
+
+    if let Some(summary) = view.summary {
+        render_summary_screen(frame, theme, summary, size);
+    } else if view.show_help {
+        render_help(frame, theme, view.key_map, size);
+    }
+}
 
-    let header = Paragraph::new(header.to_string())
-        .block(Block::default().borders(Borders::ALL).title("Project"))
-        .alignment(Alignment::Center);
+/// Panels of the inline viewport: the clients, plus the controls when the
+/// viewport is tall enough to show both.
+#[cfg(feature = "tui")]
+fn inline_panels(height: u16) -> impl Iterator<Item = (Panel, Constraint)> {
+    let controls =
+        (height >= MIN_INLINE_CONTROLS_HEIGHT).then_some((Panel::Controls, Constraint::Length(3)));
+    [(Panel::Clients, Constraint::Fill(1))]
+        .into_iter()
+        .chain(controls)
+}
 
-    let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Clients"));
+/// Render the end-of-run summary over the whole dashboard.
+#[cfg(feature = "tui")]
+fn render_summary_screen(frame: &mut Frame<'_>, theme: &Theme, summary: &RunSummary, area: Rect) {
+    let popup = area.inner(Margin::new(2, 1));
+    let screen = Paragraph::new(summary.lines(theme))
+        .block(theme.block("Summary (any key to exit)"));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(screen, popup);
+}
 
-    let info_text = if confirm_quit {
-        "Quit? (y/n)"
+/// Render the controls line: key hints, or the open prompt.
+#[cfg(feature = "tui")]
+fn render_controls<'a>(view: &FrameView<'a>) -> Paragraph<'a> {
+    let keys = view.key_map;
+    let info_text = if let Some(prompt) = view.prompt {
+        let question = match prompt {
+            Prompt::Quit => "Quit?".to_string(),
+            Prompt::Cancel { label, .. } => format!("Cancel '{label}'?"),
+        };
+        format!(
+            "{question} ({}/{})",
+            keys.describe(Action::Confirm),
+            keys.describe(Action::Dismiss)
+        )
     } else {
-        "Press 'q' to quit"
+        format!(
+            "Press '{}' to quit, '{}' for help",
+            keys.describe(Action::Quit),
+            keys.describe(Action::Help)
+        )
     };
-    let info = Paragraph::new(info_text)
-        .block(Block::default().borders(Borders::ALL).title("Controls"));
+    Paragraph::new(info_text).block(view.theme.block("Controls"))
+}
 
-    frame.render_widget(header, blocks[0]);
+/// Render client counts by status, followed by a gauge of overall progress
+/// across clients with a known total.
+#[cfg(feature = "tui")]
+fn render_summary(
+    frame: &mut Frame<'_>,
+    snapshot: &[crate::ClientState],
+    theme: &Theme,
+    area: Rect,
+) {
+    let block = theme.block("Summary");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-    frame.render_widget(list, blocks[1]);
+    let count = |status: TaskStatus| {
+        snapshot
+            .iter()
+            .filter(|state| state.status == Some(status))
+            .count()
+    };
+    let mut spans = vec![Span::raw(format!("{} clients", snapshot.len()))];
+    for (name, status) in [
+        ("setting up", TaskStatus::SettingUp),
+        ("running", TaskStatus::Running),
+        ("paused", TaskStatus::Paused),
+        ("finalizing", TaskStatus::Finalizing),
+        ("completed", TaskStatus::Completed),
+        ("failed", TaskStatus::Failed),
+        ("canceled", TaskStatus::Canceled),
+    ] {
+        let count = count(status);
+        // Setup, pauses, teardown, failures, and cancellations only take room
+        // once they happen.
+        if count == 0 && status != TaskStatus::Running && status != TaskStatus::Completed {
+            continue;
+        }
+        spans.push(Span::raw(" | "));
+        spans.push(Span::styled(
+            format!("{name} {count}"),
+            theme.status(Some(status)),
+        ));
+    }
+    spans.push(Span::raw(" | "));
+    let counts = Line::from(spans);
+
+    let (current, total) = snapshot
+        .iter()
+        .filter_map(crate::ClientState::overall_progress)
+        .fold((0u64, 0u64), |(c, t), (current, total)| {
+            (c + current, t + total)
+        });
+    let ratio = if total > 0 {
+        current as f64 / total as f64
+    } else {
+        0.0
+    };
 
-    frame.render_widget(info, blocks[2]);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(counts.width() as u16), Constraint::Fill(1)])
+        .split(inner);
+    frame.render_widget(Paragraph::new(counts), columns[0]);
+    frame.render_widget(
+        Gauge::default()
+            .ratio(ratio)
+            .label(format!("overall {:.0}%", (ratio * 100.0).floor()))
+            .use_unicode(true)
+            .gauge_style(theme.bar),
+        columns[1],
+    );
 }
 
+/// Render everything known about one client, with as many of its recent
+/// messages as fit.
 #[cfg(feature = "tui")]
-fn format_task_item(state: &crate::ClientState) -> ListItem<'_> {
-    let label = state.label.as_deref().unwrap_or("unnamed");
-    let status_str = state
-        .status
-        .map(|s| format!("{s:?}"))
-        .unwrap_or_else(|| "Unknown".to_string());
-    let current = state.current.unwrap_or(0);
-    let total = state.total;
-    let percent = total.map(|t| {
-        if t == 0 {
-            0
-        } else {
-            ((current.saturating_mul(100)) / t) as u16
-        }
-    });
+fn render_detail<'a>(
+    theme: &Theme,
+    state: Option<&'a crate::ClientState>,
+    height: u16,
+) -> Paragraph<'a> {
+    let block = theme.block("Detail");
+    let Some(state) = state else {
+        return Paragraph::new("No clients").block(block);
+    };
 
-    let bar = render_bar(percent, 20);
-    let total_str = total
+    let status = state.status;
+    let finished = status.is_some_and(TaskStatus::is_terminal);
+    let elapsed = if finished {
+        state.last_update.duration_since(state.start_time)
+    } else {
+        state.start_time.elapsed()
+    };
+    let total = state
+        .total
         .map(|t| t.to_string())
         .unwrap_or_else(|| "?".to_string());
-    let pct_str = percent
-        .map(|p| format!("{p:3}%"))
-        .unwrap_or_else(|| " ??%".to_string());
     let last_iter = state
         .last_iter_duration
         .map(format_duration)
         .unwrap_or_else(|| "--".to_string());
 
-    let status_style = match state.status {
-        Some(crate::TaskStatus::Completed) => Style::default().fg(Color::Green),
-        Some(crate::TaskStatus::Failed) | Some(crate::TaskStatus::Canceled) => {
-            Style::default().fg(Color::Red)
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(state.label.as_deref().unwrap_or("unnamed"), theme.label),
+            Span::raw(" | "),
+            Span::styled(
+                status
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                theme.status(status),
+            ),
+        ]),
+        Line::from(format!(
+            "progress {}/{total} | elapsed {} | last {last_iter}",
+            state.current.unwrap_or(0),
+            format_duration(elapsed)
+        )),
+    ];
+    if let (Some(name), Some(number)) = (&state.phase, state.phase_number()) {
+        let count = state
+            .phase_count
+            .map(|count| format!("/{count}"))
+            .unwrap_or_default();
+        let mut line = format!("phase {number}{count}: {name}");
+        let done: Vec<String> = state
+            .phases
+            .iter()
+            .map(|phase| {
+                let total = phase
+                    .total
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                format!(
+                    "{} {}/{total} in {}",
+                    phase.name,
+                    phase.completed,
+                    format_duration(phase.duration)
+                )
+            })
+            .collect();
+        if !done.is_empty() {
+            line.push_str(&format!(" | done: {}", done.join(", ")));
         }
-        _ => Style::default(),
-    };
+        lines.push(Line::from(line));
+    }
+    if !state.params.is_empty() {
+        let params: Vec<String> = state
+            .params
+            .iter()
+            .map(|(name, value)| format!("{name}={}", format_param(*value)))
+            .collect();
+        lines.push(Line::from(format!("params {}", params.join(", "))));
+    }
+    if let Some(tid) = state.thread_id {
+        lines.push(Line::from(format!("thread {tid}")));
+    }
+    if !state.metrics.is_empty() {
+        let metrics: Vec<String> = state
+            .metrics
+            .iter()
+            .map(|(name, value)| format!("{name} {}", format_metric(*value)))
+            .collect();
+        lines.push(Line::from(metrics.join(" | ")));
+    }
 
-    let line = Line::from(vec![
-        Span::styled(label.to_string(), Style::default().fg(Color::Blue)),
-        Span::raw(" | "),
-        Span::styled(status_str, status_style),
-        Span::raw(format!(
-            " | {current}/{total_str} | {bar} {pct_str} | last {last_iter}"
-        )),
-    ]);
+    let room = usize::from(height.saturating_sub(2)).saturating_sub(lines.len());
+    let skip = state.messages.len().saturating_sub(room);
+    lines.extend(
+        state.messages[skip..]
+            .iter()
+            .map(|message| Line::styled(message.as_str(), theme.dim)),
+    );
+    Paragraph::new(lines).block(block)
+}
+
+/// Render the help overlay listing every action and its keys.
+#[cfg(feature = "tui")]
+fn render_help(frame: &mut Frame<'_>, theme: &Theme, key_map: &KeyMap, area: Rect) {
+    let lines: Vec<Line> = Action::ALL
+        .iter()
+        .map(|action| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>14}  ", key_map.describe(*action)),
+                    theme.key,
+                ),
+                Span::raw(action.description()),
+            ])
+        })
+        .collect();
 
-    ListItem::new(line)
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = 56.min(area.width);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let help = Paragraph::new(lines).block(theme.block("Help (any key to close)"));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(help, popup);
 }
 
+/// Render the last lines of `lines` that fit in `height`, skipping the
+/// newest `scroll` lines.
 #[cfg(feature = "tui")]
-fn render_bar(percent: Option<u16>, width: usize) -> String {
-    let filled = percent
-        .map(|p| ((p as usize * width) / 100).min(width))
-        .unwrap_or(0);
-    let mut bar = String::with_capacity(width + 2);
-    bar.push('[');
-    for i in 0..width {
-        if i < filled {
-            bar.push('#');
-        } else {
-            bar.push('-');
-        }
+fn render_tail<'a>(
+    theme: &Theme,
+    title: &'a str,
+    lines: &'a [String],
+    scroll: usize,
+    height: u16,
+) -> List<'a> {
+    let visible = usize::from(height.saturating_sub(2));
+    let end = lines.len().saturating_sub(scroll);
+    let start = end.saturating_sub(visible);
+    let items: Vec<ListItem> = lines[start..end]
+        .iter()
+        .map(|line| ListItem::new(line.as_str()))
+        .collect();
+    List::new(items).block(theme.block(title))
+}
+
+/// Render the process resources panel: figures plus CPU and RSS sparklines.
+#[cfg(feature = "tui")]
+fn render_resources(frame: &mut Frame<'_>, theme: &Theme, resources: &ResourceSampler, area: Rect) {
+    let block = theme.block("Resources");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let stats = match resources.latest() {
+        Some(stats) => vec![
+            Line::from(format!("CPU {:.0}%", stats.cpu_percent)),
+            Line::from(format!("RSS {}", format_bytes(stats.rss_bytes))),
+            Line::from(format!("threads {}", stats.threads)),
+        ],
+        None => vec![Line::from("sampling...")],
+    };
+    // Sparklines need some room to say anything; narrow terminals get the
+    // figures alone.
+    if inner.width < MIN_SPARKLINE_WIDTH {
+        frame.render_widget(Paragraph::new(stats), inner);
+        return;
     }
-    bar.push(']');
-    bar
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(20),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ])
+        .split(inner);
+    frame.render_widget(Paragraph::new(stats), columns[0]);
+
+    let cpu = resources.cpu_history();
+    let rss = resources.rss_history();
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().title("CPU %").title_style(theme.title))
+            .data(tail(&cpu, columns[1].width))
+            .style(theme.cpu),
+        columns[1],
+    );
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().title("RSS").title_style(theme.title))
+            .data(tail(&rss, columns[2].width))
+            .style(theme.memory),
+        columns[2],
+    );
+}
+
+/// Return the last `width` samples so sparklines show the newest data.
+#[cfg(feature = "tui")]
+fn tail(data: &[u64], width: u16) -> &[u64] {
+    &data[data.len().saturating_sub(usize::from(width))..]
 }
 
+/// Format a duration compactly, from milliseconds up to hours.
 #[cfg(feature = "tui")]
-fn format_duration(duration: Duration) -> String {
+pub(crate) fn format_duration(duration: Duration) -> String {
     let secs = duration.as_secs();
     if secs < 1 {
         return format!("{}ms", duration.as_millis());
@@ -0,0 +1,223 @@
+//! ==============================================================================
+//! src/summary.rs
+//! End-of-run summary: per-client outcome, duration, and throughput.
+//! ==============================================================================
+
+use std::time::Duration;
+
+use ratatui::text::{Line, Span};
+
+use crate::gauge;
+use crate::runtime::format_duration;
+use crate::theme::Theme;
+use crate::{ClientState, TaskStatus};
+
+/// Widest label column in the summary; longer labels are truncated.
+const MAX_LABEL_WIDTH: usize = 32;
+
+/// Outcome of a run, built from the final client states.
+pub(crate) struct RunSummary {
+    /// Time from the start of the runtime session to the summary.
+    wall: Duration,
+    clients: Vec<ClientSummary>,
+}
+
+/// Outcome of a single client.
+struct ClientSummary {
+    label: String,
+    status: Option<TaskStatus>,
+    /// Progress across all phases, as in the client table.
+    current: u64,
+    total: Option<u64>,
+    duration: Duration,
+    /// Last message of a failed client, usually the error.
+    error: Option<String>,
+}
+
+impl RunSummary {
+    /// Summarize `snapshot`, which should be in display order.
+    pub(crate) fn new(snapshot: &[ClientState], wall: Duration) -> Self {
+        let clients = snapshot
+            .iter()
+            .map(|state| {
+                let (current, total) = match state.overall_progress() {
+                    Some((current, total)) => (current, Some(total)),
+                    None => {
+                        let ended: u64 = state.phases.iter().map(|phase| phase.completed).sum();
+                        (ended + state.current.unwrap_or(0), None)
+                    }
+                };
+                ClientSummary {
+                    label: state.label.clone().unwrap_or_else(|| "unnamed".to_string()),
+                    status: state.status,
+                    current,
+                    total,
+                    duration: state.last_update.duration_since(state.start_time),
+                    error: (state.status == Some(TaskStatus::Failed))
+                        .then(|| state.messages.last().cloned())
+                        .flatten(),
+                }
+            })
+            .collect();
+        Self { wall, clients }
+    }
+
+    /// Render the summary as styled lines for the summary screen.
+    pub(crate) fn lines(&self, theme: &Theme) -> Vec<Line<'static>> {
+        let label_width = self.label_width();
+        let mut lines = vec![Line::styled(self.headline(), theme.title), Line::default()];
+        for client in &self.clients {
+            lines.push(Line::from(vec![
+                Span::styled(gauge::fit(&client.label, label_width), theme.label),
+                Span::raw("  "),
+                Span::styled(
+                    gauge::fit(&status_name(client.status), 10),
+                    theme.status(client.status),
+                ),
+                Span::raw(format!("  {}", client.figures())),
+            ]));
+        }
+        let failures = self.failure_lines();
+        if !failures.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::styled("Failures:", theme.failed));
+            lines.extend(failures.into_iter().map(Line::raw));
+        }
+        lines
+    }
+
+    /// Render the summary as plain text for the normal terminal.
+    pub(crate) fn to_text(&self) -> String {
+        let label_width = self.label_width();
+        let mut text = format!("{}\n", self.headline());
+        for client in &self.clients {
+            text.push_str(&format!(
+                "  {}  {}  {}\n",
+                gauge::fit(&client.label, label_width),
+                gauge::fit(&status_name(client.status), 10),
+                client.figures()
+            ));
+        }
+        let failures = self.failure_lines();
+        if !failures.is_empty() {
+            text.push_str("Failures:\n");
+            for line in failures {
+                text.push_str(&format!("{line}\n"));
+            }
+        }
+        text
+    }
+
+    /// One line with the run's totals.
+    fn headline(&self) -> String {
+        let count = |status: TaskStatus| {
+            self.clients
+                .iter()
+                .filter(|client| client.status == Some(status))
+                .count()
+        };
+        let iterations: u64 = self.clients.iter().map(|client| client.current).sum();
+        let mut outcome = vec![format!("{} completed", count(TaskStatus::Completed))];
+        for (name, status) in [
+            ("failed", TaskStatus::Failed),
+            ("canceled", TaskStatus::Canceled),
+        ] {
+            if count(status) > 0 {
+                outcome.push(format!("{} {name}", count(status)));
+            }
+        }
+        let unfinished = self
+            .clients
+            .iter()
+            .filter(|client| !client.status.is_some_and(TaskStatus::is_terminal))
+            .count();
+        if unfinished > 0 {
+            outcome.push(format!("{unfinished} unfinished"));
+        }
+        format!(
+            "Run summary: {} clients in {} ({}), {iterations} iterations at {}",
+            self.clients.len(),
+            format_duration(self.wall),
+            outcome.join(", "),
+            format_throughput(iterations, self.wall)
+        )
+    }
+
+    /// Indented `label: error` lines for failed clients.
+    fn failure_lines(&self) -> Vec<String> {
+        self.clients
+            .iter()
+            .filter(|client| client.status == Some(TaskStatus::Failed))
+            .map(|client| match &client.error {
+                Some(error) => format!("  {}: {error}", client.label),
+                None => format!("  {}", client.label),
+            })
+            .collect()
+    }
+
+    fn label_width(&self) -> usize {
+        self.clients
+            .iter()
+            .map(|client| gauge::display_width(&client.label))
+            .max()
+            .unwrap_or(0)
+            .min(MAX_LABEL_WIDTH)
+    }
+}
+
+impl ClientSummary {
+    /// Progress, duration, and throughput columns.
+    fn figures(&self) -> String {
+        let total = self
+            .total
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "?".to_string());
+        format!(
+            "{:>15}  {:>8}  {}",
+            format!("{}/{total}", self.current),
+            format_duration(self.duration),
+            format_throughput(self.current, self.duration)
+        )
+    }
+}
+
+fn status_name(status: Option<TaskStatus>) -> String {
+    status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Format iterations per second over `duration`.
+fn format_throughput(iterations: u64, duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs > 0.0 {
+        format!("{:.1} it/s", iterations as f64 / secs)
+    } else {
+        "-- it/s".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Phase;
+
+    #[test]
+    fn phased_clients_are_summarized_across_all_phases() {
+        let mut state = ClientState::new("sim", Some(1_000));
+        state.status = Some(TaskStatus::Running);
+        state.current = Some(340);
+        state.phase = Some("production".to_string());
+        state.phase_count = Some(2);
+        state.phases = vec![Phase {
+            name: "warmup".to_string(),
+            completed: 500,
+            total: Some(500),
+            duration: Duration::from_secs(1),
+        }];
+
+        let text = RunSummary::new(&[state], Duration::from_secs(1)).to_text();
+        assert!(text.contains("840 iterations"), "{text}");
+        assert!(text.contains("840/1500"), "{text}");
+    }
+}
@@ -0,0 +1,290 @@
+//! ==============================================================================
+//! src/sweep.rs
+//! Parameter sweeps: tasks for every point of a grid of named axes.
+//! ==============================================================================
+
+use std::fmt;
+use std::ops::Index;
+
+use crate::Task;
+
+/// Most decimal places tried when formatting a parameter value for a label.
+const MAX_LABEL_PRECISION: usize = 12;
+
+/// Builder for the Cartesian product of named parameter axes.
+///
+/// Each point of the product becomes one task, built by a factory and
+/// labeled with its parameters, for example `T=1.5,L=64`.
+#[derive(Debug, Clone, Default)]
+pub struct Sweep {
+    axes: Vec<(String, Vec<f64>)>,
+}
+
+impl Sweep {
+    /// Create a sweep with no axes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an axis taking each of `values`, in order.
+    ///
+    /// An axis with the same name as an earlier one replaces it.
+    pub fn list(mut self, name: impl Into<String>, values: impl IntoIterator<Item = f64>) -> Self {
+        let name = name.into();
+        let values = values.into_iter().collect();
+        match self.axes.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, existing)) => *existing = values,
+            None => self.axes.push((name, values)),
+        }
+        self
+    }
+
+    /// Add an axis of `count` evenly spaced values from `start` to `end`,
+    /// both included.
+    pub fn linspace(self, name: impl Into<String>, start: f64, end: f64, count: usize) -> Self {
+        let values = spaced(count, |t| start + (end - start) * t, end);
+        self.list(name, values)
+    }
+
+    /// Add an axis of `count` geometrically spaced values from `start` to
+    /// `end`, both included, such as `1, 10, 100`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `start` and `end` are both positive.
+    pub fn logspace(self, name: impl Into<String>, start: f64, end: f64, count: usize) -> Self {
+        assert!(
+            start > 0.0 && end > 0.0,
+            "logspace bounds must be positive, got {start} and {end}"
+        );
+        let values = spaced(count, |t| start * (end / start).powf(t), end);
+        self.list(name, values)
+    }
+
+    /// Return every point of the sweep, varying the last axis fastest.
+    ///
+    /// A sweep with no axes has a single point without parameters; an axis
+    /// without values leaves none.
+    pub fn points(&self) -> Vec<Params> {
+        let mut points = vec![Vec::new()];
+        for (name, values) in &self.axes {
+            points = points
+                .into_iter()
+                .flat_map(|point: Vec<(String, f64)>| {
+                    values.iter().map(move |value| {
+                        let mut point = point.clone();
+                        point.push((name.clone(), *value));
+                        point
+                    })
+                })
+                .collect();
+        }
+        points.into_iter().map(Params).collect()
+    }
+
+    /// Build one task per point with `factory`, in the order of `points`.
+    ///
+    /// Each task is labeled with its parameters and reports them to its
+    /// client, so the TUI can sort by them.
+    pub fn tasks<T: Task>(&self, mut factory: impl FnMut(&Params) -> T) -> Vec<SweepTask<T>> {
+        self.points()
+            .into_iter()
+            .map(|params| SweepTask {
+                label: params.to_string(),
+                task: factory(&params),
+                params,
+            })
+            .collect()
+    }
+}
+
+/// `count` values of `at(t)` for `t` evenly spaced over `0..=1`, with the
+/// last one exactly `end` so rounding never moves the upper bound.
+fn spaced(count: usize, at: impl Fn(f64) -> f64, end: f64) -> Vec<f64> {
+    (0..count)
+        .map(|i| match i {
+            0 => at(0.0),
+            i if i + 1 == count => end,
+            i => at(i as f64 / (count - 1) as f64),
+        })
+        .collect()
+}
+
+/// Named parameter values of one point of a `Sweep`, in axis order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Params(Vec<(String, f64)>);
+
+impl Params {
+    /// Return the value of the parameter `name`, if the sweep has that axis.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Iterate over the parameter names and values, in axis order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Return the parameters as `(name, value)` pairs, as reported to the
+    /// task's client.
+    pub fn to_vec(&self) -> Vec<(String, f64)> {
+        self.0.clone()
+    }
+}
+
+impl Index<&str> for Params {
+    type Output = f64;
+
+    /// # Panics
+    ///
+    /// Panics if the sweep has no axis called `name`.
+    fn index(&self, name: &str) -> &f64 {
+        self.0
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("no sweep parameter named {name:?}"))
+    }
+}
+
+/// Formats as `name=value` pairs joined by commas, such as `T=1.5,L=64`.
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{name}={}", format_param(*value))?;
+        }
+        Ok(())
+    }
+}
+
+/// Format `value` with the fewest decimal places that still identify it, so
+/// a linspace step such as `0.30000000000000004` is labeled `0.3`.
+pub(crate) fn format_param(value: f64) -> String {
+    // Adding zero turns `-0.0` into `0.0`, so it is not labeled `-0`.
+    let value = value + 0.0;
+    let magnitude = value.abs();
+    let scientific = magnitude != 0.0 && !(1e-4..1e6).contains(&magnitude);
+    let close = |text: &str| {
+        text.parse::<f64>()
+            .is_ok_and(|parsed| (parsed - value).abs() <= magnitude * 1e-9)
+    };
+    (0..=MAX_LABEL_PRECISION)
+        .map(|precision| {
+            if scientific {
+                format!("{value:.precision$e}")
+            } else {
+                format!("{value:.precision$}")
+            }
+        })
+        .find(|text| close(text))
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// A task built by `Sweep::tasks`, labeled with its sweep point.
+///
+/// Every `Task` method other than `label` and `params` is forwarded to the
+/// wrapped task, whose own label is not used.
+#[derive(Debug, Clone)]
+pub struct SweepTask<T> {
+    params: Params,
+    label: String,
+    task: T,
+}
+
+impl<T> SweepTask<T> {
+    /// Return the sweep point of this task.
+    pub fn point(&self) -> &Params {
+        &self.params
+    }
+
+    /// Return the wrapped task.
+    pub fn inner(&self) -> &T {
+        &self.task
+    }
+
+    /// Return the wrapped task, dropping the sweep point.
+    pub fn into_inner(self) -> T {
+        self.task
+    }
+}
+
+impl<T: Task> Task for SweepTask<T> {
+    type Output = T::Output;
+
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn total_iters(&self) -> u64 {
+        self.task.total_iters()
+    }
+
+    fn params(&self) -> Vec<(String, f64)> {
+        self.params.to_vec()
+    }
+
+    fn setup(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.task.setup()
+    }
+
+    fn workload_per_iter(&mut self) {
+        self.task.workload_per_iter();
+    }
+
+    fn teardown(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.task.teardown()
+    }
+
+    fn finish(self) -> Self::Output {
+        self.task.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_vary_the_last_axis_fastest() {
+        let sweep = Sweep::new()
+            .list("a", [1.0, 2.0])
+            .list("b", [10.0, 20.0, 30.0]);
+        let labels: Vec<String> = sweep.points().iter().map(Params::to_string).collect();
+
+        assert_eq!(
+            labels,
+            ["a=1,b=10", "a=1,b=20", "a=1,b=30", "a=2,b=10", "a=2,b=20", "a=2,b=30"]
+        );
+    }
+
+    #[test]
+    fn spaced_axes_end_exactly_on_their_bounds() {
+        let sweep = Sweep::new()
+            .linspace("x", 0.0, 0.3, 4)
+            .logspace("y", 1.0, 100.0, 3);
+        let points = sweep.points();
+
+        assert_eq!(points.len(), 12);
+        assert_eq!(points[0].to_string(), "x=0,y=1");
+        assert_eq!(points[4].to_string(), "x=0.1,y=10");
+        assert_eq!(points[11]["x"], 0.3);
+        assert_eq!(points[11]["y"], 100.0);
+    }
+
+    #[test]
+    fn params_are_formatted_with_the_fewest_digits() {
+        assert_eq!(format_param(0.1), "0.1");
+        assert_eq!(format_param(0.1 + 0.2), "0.3");
+        assert_eq!(format_param(64.0), "64");
+        assert_eq!(format_param(1e-5), "1e-5");
+        assert_eq!(format_param(2.5e7), "2.5e7");
+        assert_eq!(format_param(-0.0), "0");
+        assert_eq!(format_param(-1.5), "-1.5");
+    }
+}
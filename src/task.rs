@@ -1,20 +1,88 @@
-/// ==============================================================================
-/// src/task.rs
-/// Task trait for workload-driven scientific objects.
-/// ==============================================================================
+//! ==============================================================================
+//! src/task.rs
+//! Task trait for workload-driven scientific objects.
+//! ==============================================================================
+
+use std::fmt;
 
 /// A single unit of work executed by the runner.
 ///
 /// Implement this on your scientific object. The launcher will call
-/// `workload_per_iter` for each tick and report progress using
-/// `total_iters`.
+/// `setup`, then `workload_per_iter` for each tick, reporting progress using
+/// `total_iters`, then `teardown`, all on the task's worker thread. Once
+/// every step has run, `finish` turns the task into its result, which the
+/// launcher hands back to the caller.
 pub trait Task: Send + 'static {
+    /// Result produced by a finished task; use `()` if there is none.
+    type Output: Send + 'static;
+
     /// Human-readable label for the task.
     fn label(&self) -> &str;
 
     /// Total iterations for this task.
     fn total_iters(&self) -> u64;
 
+    /// Named parameters of the task, attached to its client so the TUI can
+    /// sort by them. The default has none; `Sweep` tasks report their point.
+    fn params(&self) -> Vec<(String, f64)> {
+        Vec::new()
+    }
+
+    /// Prepare for the first iteration, for example by allocating buffers or
+    /// opening files. The TUI shows the task as "Setting up" meanwhile.
+    ///
+    /// An error or panic fails the task. The default does nothing.
+    fn setup(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
     /// Perform one unit of work.
     fn workload_per_iter(&mut self);
+
+    /// Clean up after the last iteration, for example by flushing output
+    /// files. The TUI shows the task as "Finalizing" meanwhile.
+    ///
+    /// Only called once every iteration has run, so not for canceled or
+    /// failed tasks; use `Drop` for cleanup that must always happen. An error
+    /// or panic fails the task. The default does nothing.
+    fn teardown(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Consume the task after its last iteration and return its result.
+    fn finish(self) -> Self::Output;
 }
+
+/// Why a task produced no output.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskError {
+    /// `setup` returned an error or panicked; holds its message.
+    Setup(String),
+    /// An iteration or `finish` panicked; holds the panic message.
+    Panicked(String),
+    /// `teardown` returned an error or panicked; holds its message.
+    Teardown(String),
+    /// The task was canceled before its last iteration.
+    Canceled,
+    /// The launch ended before the task started.
+    NotStarted,
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Setup(message) => write!(f, "setup failed: {message}"),
+            TaskError::Panicked(message) => write!(f, "task panicked: {message}"),
+            TaskError::Teardown(message) => write!(f, "teardown failed: {message}"),
+            TaskError::Canceled => write!(f, "task was canceled"),
+            TaskError::NotStarted => write!(f, "task never started"),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+/// Output of each task of a launch, with its label, in the order the tasks
+/// were given.
+pub type TaskOutputs<O> = Vec<(String, Result<O, TaskError>)>;
@@ -1,13 +1,17 @@
-/// ==============================================================================
-/// src/task_group.rs
-/// Task group runner for parallel execution and reporting.
-/// ==============================================================================
+//! ==============================================================================
+//! src/task_group.rs
+//! Task group runner for parallel execution and reporting.
+//! ==============================================================================
 
 use std::thread;
 
-use crate::{ClientStore, Task};
-#[cfg(feature = "tui")]
-use crate::Runtime;
+use crate::retry::catch_panic;
+use crate::{
+    ClientHandle, ClientReporter, LaunchOutcome, Launcher, RetryPolicy, Task, TaskError, TaskId,
+};
+
+/// Capacity of the bounded store channel used by the launchers.
+pub(crate) const DEFAULT_CHANNEL_CAPACITY: usize = 4_096;
 
 /// A group of tasks executed in parallel.
 ///
@@ -21,59 +25,153 @@ pub trait TaskGroup: Send + 'static {
     /// Return the tasks managed by this group.
     fn tasks(self) -> Vec<Self::Task>;
 
-    /// Launch all tasks until their total iteration limit is hit.
-    fn launch(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    /// Adjust how `launch` runs the group: frame rate, label, concurrency,
+    /// renderer, exporters, end behavior, or retries.
+    ///
+    /// The default keeps the launcher as it is.
+    fn configure(&self, launcher: Launcher) -> Launcher {
+        launcher
+    }
+
+    /// Launch all tasks until their total iteration limit is hit, and return
+    /// their outputs and how each of them ended.
+    fn launch(
+        self,
+    ) -> Result<LaunchOutcome<<Self::Task as Task>::Output>, Box<dyn std::error::Error + Send + Sync>>
     where
         Self: Sized,
     {
-        launch_tasks(self.tasks(), 20, None)
+        let launcher = self.configure(Launcher::new());
+        launcher.launch(self.tasks())
     }
 }
 
-/// Helper that launches a vector of tasks with standard TUI behavior.
+/// Helper that launches a vector of tasks with standard TUI behavior, and
+/// returns their outputs, in the order the tasks were given, and the report.
+///
+/// This is shorthand for `Launcher::new().fps(runtime_fps).launch(tasks)`
+/// with an optional label; use `Launcher` for every other option.
 pub fn launch_tasks<T: Task>(
     tasks: Vec<T>,
     runtime_fps: u64,
     runtime_project_label: Option<String>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let runtime_quit_on_q = true;
-    let (reporter, mut store) = ClientStore::new();
-    let mut handles = Vec::with_capacity(tasks.len());
+) -> Result<LaunchOutcome<T::Output>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut launcher = Launcher::new().fps(runtime_fps);
+    if let Some(label) = runtime_project_label {
+        launcher = launcher.label(label);
+    }
+    launcher.launch(tasks)
+}
 
-    for mut task in tasks {
-        let reporter = reporter.clone();
-        let label = task.label().to_string();
-        let total_iters = task.total_iters();
-        let total = Some(total_iters);
+/// Label, total iterations, and parameters of a task.
+pub(crate) type Description = (String, u64, Vec<(String, f64)>);
 
-        let handle = thread::spawn(move || {
-            let client = reporter.start(label, total);
-            if let Ok(client) = client {
-                for step in 0..total_iters {
-                    task.workload_per_iter();
-                    let _ = client.set_current(step.saturating_add(1));
-                }
-                let _ = client.set_current(total_iters);
-                let _ = client.complete();
-            }
-        });
+/// Read the label, total iterations, and parameters of `task`, or the
+/// message of a panic in any of them.
+pub(crate) fn describe<T: Task>(task: &T) -> Result<Description, String> {
+    catch_panic(|| (task.label().to_string(), task.total_iters(), task.params()))
+}
 
-        handles.push(handle);
+/// Run a single task to completion on the current thread, reporting progress,
+/// and return its label and output.
+///
+/// Shared by every launcher so that all execution backends report the
+/// same lifecycle to the store. An iteration that panics is retried as
+/// `retry` allows; after that, the panic message is logged and the client
+/// fails. Errors and panics in `setup`, `teardown`, or `finish` fail the
+/// client the same way. A panic in `label`, `total_iters`, or `params` fails
+/// the task before its client starts, with an empty label. `id` of `None`
+/// lets the reporter pick the client id.
+pub(crate) fn run_task<T: Task>(
+    task: T,
+    reporter: &ClientReporter,
+    retry: &RetryPolicy,
+    id: Option<TaskId>,
+) -> (String, Result<T::Output, TaskError>) {
+    match describe(&task) {
+        Ok(description) => {
+            let label = description.0.clone();
+            (label, run_described(task, description, reporter, retry, id))
+        }
+        Err(message) => (String::new(), Err(TaskError::Panicked(message))),
     }
+}
 
-    #[cfg(feature = "tui")]
-    {
-        let mut runtime = Runtime::new(runtime_fps);
-        runtime = runtime.quit_on_q(runtime_quit_on_q);
-        if let Some(label) = runtime_project_label {
-            runtime = runtime.project_label(label);
+/// Run a task whose `describe` succeeded; see `run_task`.
+fn run_described<T: Task>(
+    mut task: T,
+    (label, total_iters, params): Description,
+    reporter: &ClientReporter,
+    retry: &RetryPolicy,
+    id: Option<TaskId>,
+) -> Result<T::Output, TaskError> {
+    let client = match id {
+        Some(id) => reporter.start_with_id(id, label, Some(total_iters)),
+        None => reporter.start(label, Some(total_iters)),
+    };
+    let Ok(client) = client else {
+        return Err(TaskError::NotStarted);
+    };
+    let _guard = client.enter();
+    let _ = client.attach_current_thread();
+    if !params.is_empty() {
+        let _ = client.set_params(params);
+    }
+    let _ = client.setting_up();
+    run_step(&client, "setup", || task.setup()).map_err(TaskError::Setup)?;
+    let _ = client.running();
+    for step in 0..total_iters {
+        let _ = client.checkpoint();
+        if client.cancel_requested() {
+            let _ = client.cancel();
+            return Err(TaskError::Canceled);
+        }
+        let mut retries = 0;
+        while let Err(message) = catch_panic(|| task.workload_per_iter()) {
+            let iteration = step.saturating_add(1);
+            if retries == retry.max_retries() {
+                let _ = client.log(format!("iteration {iteration} panicked: {message}"));
+                let _ = client.fail();
+                return Err(TaskError::Panicked(message));
+            }
+            retries += 1;
+            let _ = client.log(format!(
+                "iteration {iteration} panicked: {message}; retry {retries}/{}",
+                retry.max_retries()
+            ));
+            thread::sleep(retry.delay());
         }
-        runtime.run(&mut store)?;
+        let _ = client.set_current(step.saturating_add(1));
     }
-
-    for handle in handles {
-        let _ = handle.join();
+    let _ = client.set_progress(total_iters, Some(total_iters));
+    let _ = client.finalizing();
+    run_step(&client, "teardown", || task.teardown()).map_err(TaskError::Teardown)?;
+    match catch_panic(|| task.finish()) {
+        Ok(output) => {
+            let _ = client.complete();
+            Ok(output)
+        }
+        Err(message) => {
+            let _ = client.log(format!("finish panicked: {message}"));
+            let _ = client.fail();
+            Err(TaskError::Panicked(message))
+        }
     }
+}
 
-    Ok(())
+/// Run `setup` or `teardown`; if it fails or panics, log why and fail the
+/// client.
+fn run_step(
+    client: &ClientHandle,
+    name: &str,
+    step: impl FnOnce() -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+) -> Result<(), String> {
+    let message = match catch_panic(step) {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(err)) => err.to_string(),
+        Err(message) => format!("panicked: {message}"),
+    };
+    let _ = client.log(format!("{name} failed: {message}"));
+    let _ = client.fail();
+    Err(message)
 }
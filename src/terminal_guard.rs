@@ -0,0 +1,175 @@
+//! ==============================================================================
+//! src/terminal_guard.rs
+//! Panic and signal handling while the runtime owns the terminal.
+//! ==============================================================================
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::io;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+
+use crate::retry;
+
+/// Whether a runtime session currently owns the terminal.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Pending interruption: `NONE`, `INTERRUPT`, or `TERMINATE`.
+static INTERRUPTION: AtomicU8 = AtomicU8::new(NONE);
+
+/// Panic messages recorded during the session, printed once the terminal is
+/// restored.
+static PANICS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+static INSTALL_HOOK: Once = Once::new();
+
+const NONE: u8 = 0;
+/// SIGINT, or Ctrl-C read as a key in raw mode.
+const INTERRUPT: u8 = 1;
+/// SIGTERM.
+const TERMINATE: u8 = 2;
+
+/// Handlers active for the length of one runtime session.
+///
+/// While the guard is alive, panics on any thread are recorded instead of
+/// being printed over the dashboard, and SIGINT/SIGTERM only set a flag. The
+/// runtime checks `status` every frame, so either ends the loop, restores the
+/// terminal, and makes `run` return an error. Panics that a task launcher
+/// catches are left to it, since it reports them as task failures.
+pub(crate) struct TerminalGuard {
+    /// Signal dispositions to put back when the session ends.
+    #[cfg(unix)]
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+}
+
+impl TerminalGuard {
+    /// Install the panic hook (once per process) and the signal handlers.
+    pub(crate) fn install() -> Self {
+        INSTALL_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if !ACTIVE.load(Ordering::SeqCst) {
+                    previous(info);
+                } else if !retry::panic_is_caught() {
+                    record_panic(info);
+                }
+            }));
+        });
+        INTERRUPTION.store(NONE, Ordering::SeqCst);
+        if let Ok(mut panics) = PANICS.lock() {
+            panics.clear();
+        }
+        ACTIVE.store(true, Ordering::SeqCst);
+
+        Self {
+            #[cfg(unix)]
+            previous: [libc::SIGINT, libc::SIGTERM]
+                .into_iter()
+                .filter_map(|signal| unsafe { set_handler(signal) }.map(|old| (signal, old)))
+                .collect(),
+        }
+    }
+
+    /// Run one frame, turning a panic on the runtime thread into an error.
+    pub(crate) fn frame<T>(&self, frame: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+        panic::catch_unwind(AssertUnwindSafe(frame))
+            .unwrap_or_else(|_| Err(io::Error::other("the dashboard panicked")))
+    }
+
+    /// Return an error if the session was interrupted or a thread panicked.
+    pub(crate) fn status(&self) -> io::Result<()> {
+        if PANICS
+            .lock()
+            .map(|panics| !panics.is_empty())
+            .unwrap_or(true)
+        {
+            return Err(io::Error::other(
+                "a thread panicked while the dashboard was running",
+            ));
+        }
+        match INTERRUPTION.load(Ordering::SeqCst) {
+            INTERRUPT => Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "interrupted by Ctrl-C or SIGINT",
+            )),
+            TERMINATE => Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "terminated by SIGTERM",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Treat Ctrl-C, which raw mode delivers as a key, like SIGINT.
+    pub(crate) fn interrupt(&self) {
+        INTERRUPTION.store(INTERRUPT, Ordering::SeqCst);
+    }
+
+    /// End the session and return the panic messages recorded during it.
+    ///
+    /// Call this after restoring the terminal, so the messages can be printed.
+    pub(crate) fn finish(self) -> Vec<String> {
+        ACTIVE.store(false, Ordering::SeqCst);
+        PANICS
+            .lock()
+            .map(|mut panics| std::mem::take(&mut *panics))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        ACTIVE.store(false, Ordering::SeqCst);
+        #[cfg(unix)]
+        for (signal, old) in &self.previous {
+            unsafe { libc::sigaction(*signal, old, std::ptr::null_mut()) };
+        }
+    }
+}
+
+/// Format a panic the way the default hook would and keep it for later.
+fn record_panic(info: &PanicHookInfo<'_>) {
+    let thread = thread::current();
+    let payload = retry::panic_message(info.payload());
+    let mut message = format!(
+        "thread '{}' panicked at {}:\n{payload}",
+        thread.name().unwrap_or("<unnamed>"),
+        info.location()
+            .map(|location| location.to_string())
+            .unwrap_or_default()
+    );
+    let backtrace = Backtrace::capture();
+    if backtrace.status() == BacktraceStatus::Captured {
+        message.push_str(&format!("\nstack backtrace:\n{backtrace}"));
+    }
+    match PANICS.lock() {
+        Ok(mut panics) => panics.push(message),
+        Err(poisoned) => poisoned.into_inner().push(message),
+    }
+}
+
+/// Point `signal` at `on_signal`, returning the previous disposition.
+///
+/// # Safety
+///
+/// `on_signal` only stores to an atomic, which is async-signal-safe.
+#[cfg(unix)]
+unsafe fn set_handler(signal: libc::c_int) -> Option<libc::sigaction> {
+    let mut action: libc::sigaction = std::mem::zeroed();
+    action.sa_sigaction = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    action.sa_flags = libc::SA_RESTART;
+    libc::sigemptyset(&mut action.sa_mask);
+    let mut old: libc::sigaction = std::mem::zeroed();
+    (libc::sigaction(signal, &action, &mut old) == 0).then_some(old)
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    let interruption = if signal == libc::SIGTERM {
+        TERMINATE
+    } else {
+        INTERRUPT
+    };
+    INTERRUPTION.store(interruption, Ordering::SeqCst);
+}
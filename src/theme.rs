@@ -0,0 +1,195 @@
+//! ==============================================================================
+//! src/theme.rs
+//! Color themes for the runtime TUI.
+//! ==============================================================================
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders};
+
+use crate::TaskStatus;
+
+/// Styles used by every element of the runtime TUI.
+///
+/// Start from a preset and override individual fields as needed. The
+/// default is `Theme::dark()`, or `Theme::monochrome()` when the `NO_COLOR`
+/// environment variable is set; a theme passed to `Runtime::theme` is used
+/// as given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Panel borders.
+    pub border: Style,
+    /// Panel titles.
+    pub title: Style,
+    /// Column headers of the clients table.
+    pub header: Style,
+    /// Client labels.
+    pub label: Style,
+    /// Status of running clients.
+    pub running: Style,
+    /// Status of paused clients.
+    pub paused: Style,
+    /// Status of completed clients.
+    pub completed: Style,
+    /// Status of failed clients.
+    pub failed: Style,
+    /// Status of canceled clients.
+    pub canceled: Style,
+    /// Secondary text such as client messages.
+    pub dim: Style,
+    /// Filled part of progress bars.
+    pub bar: Style,
+    /// Key names in the help overlay and prompts.
+    pub key: Style,
+    /// CPU sparkline in the resources panel.
+    pub cpu: Style,
+    /// Memory sparkline in the resources panel.
+    pub memory: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        if no_color() {
+            Self::monochrome()
+        } else {
+            Self::dark()
+        }
+    }
+}
+
+impl Theme {
+    /// Colors tuned for dark terminal backgrounds; the historical look.
+    pub fn dark() -> Self {
+        Self {
+            border: Style::default(),
+            title: Style::default(),
+            header: Style::default().add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::Blue),
+            running: Style::default(),
+            paused: Style::default().fg(Color::Yellow),
+            completed: Style::default().fg(Color::Green),
+            failed: Style::default().fg(Color::Red),
+            canceled: Style::default().fg(Color::Red),
+            dim: Style::default().add_modifier(Modifier::DIM),
+            bar: Style::default().fg(Color::Blue),
+            key: Style::default().add_modifier(Modifier::BOLD),
+            cpu: Style::default().fg(Color::Yellow),
+            memory: Style::default().fg(Color::Cyan),
+        }
+    }
+
+    /// Darker hues that stay readable on light backgrounds.
+    pub fn light() -> Self {
+        Self {
+            border: Style::default().fg(Color::DarkGray),
+            title: Style::default().fg(Color::Black).add_modifier(Modifier::BOLD),
+            header: Style::default().fg(Color::Black).add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::Indexed(18)),
+            running: Style::default().fg(Color::Black),
+            paused: Style::default().fg(Color::Indexed(136)),
+            completed: Style::default().fg(Color::Indexed(22)),
+            failed: Style::default().fg(Color::Indexed(124)),
+            canceled: Style::default().fg(Color::Indexed(94)),
+            dim: Style::default().fg(Color::DarkGray),
+            bar: Style::default().fg(Color::Indexed(18)),
+            key: Style::default().fg(Color::Black).add_modifier(Modifier::BOLD),
+            cpu: Style::default().fg(Color::Indexed(130)),
+            memory: Style::default().fg(Color::Indexed(24)),
+        }
+    }
+
+    /// Bright, bold colors with maximum contrast.
+    pub fn high_contrast() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Self {
+            border: Style::default().fg(Color::White),
+            title: bold.fg(Color::White),
+            header: bold.fg(Color::White).add_modifier(Modifier::UNDERLINED),
+            label: bold.fg(Color::LightCyan),
+            running: bold.fg(Color::White),
+            paused: bold.fg(Color::LightYellow),
+            completed: bold.fg(Color::LightGreen),
+            failed: bold.fg(Color::White).bg(Color::Red),
+            canceled: bold.fg(Color::Black).bg(Color::Yellow),
+            dim: Style::default().fg(Color::Gray),
+            bar: bold.fg(Color::LightCyan),
+            key: bold.fg(Color::LightYellow),
+            cpu: Style::default().fg(Color::LightYellow),
+            memory: Style::default().fg(Color::LightCyan),
+        }
+    }
+
+    /// Okabe-Ito palette that avoids red/green distinctions; states also
+    /// differ in weight so they never rely on hue alone.
+    pub fn colorblind() -> Self {
+        let blue = Color::Rgb(0, 114, 178);
+        let sky = Color::Rgb(86, 180, 233);
+        let orange = Color::Rgb(230, 159, 0);
+        let vermillion = Color::Rgb(213, 94, 0);
+        let yellow = Color::Rgb(240, 228, 66);
+        Self {
+            border: Style::default(),
+            title: Style::default(),
+            header: Style::default().add_modifier(Modifier::BOLD),
+            label: Style::default().fg(sky),
+            running: Style::default(),
+            paused: Style::default().fg(yellow),
+            completed: Style::default().fg(blue).add_modifier(Modifier::BOLD),
+            failed: Style::default()
+                .fg(vermillion)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            canceled: Style::default().fg(orange).add_modifier(Modifier::ITALIC),
+            dim: Style::default().add_modifier(Modifier::DIM),
+            bar: Style::default().fg(sky),
+            key: Style::default().add_modifier(Modifier::BOLD),
+            cpu: Style::default().fg(yellow),
+            memory: Style::default().fg(sky),
+        }
+    }
+
+    /// No colors at all; states are distinguished by text attributes.
+    pub fn monochrome() -> Self {
+        Self {
+            border: Style::default(),
+            title: Style::default(),
+            header: Style::default().add_modifier(Modifier::UNDERLINED),
+            label: Style::default().add_modifier(Modifier::BOLD),
+            running: Style::default(),
+            paused: Style::default().add_modifier(Modifier::ITALIC),
+            completed: Style::default().add_modifier(Modifier::BOLD),
+            failed: Style::default().add_modifier(Modifier::REVERSED),
+            canceled: Style::default().add_modifier(Modifier::UNDERLINED),
+            dim: Style::default().add_modifier(Modifier::DIM),
+            bar: Style::default(),
+            key: Style::default().add_modifier(Modifier::BOLD),
+            cpu: Style::default(),
+            memory: Style::default(),
+        }
+    }
+
+    /// Return the style for a client status.
+    pub fn status(&self, status: Option<TaskStatus>) -> Style {
+        match status {
+            Some(TaskStatus::Paused) => self.paused,
+            Some(TaskStatus::Completed) => self.completed,
+            Some(TaskStatus::Failed) => self.failed,
+            Some(TaskStatus::Canceled) => self.canceled,
+            _ => self.running,
+        }
+    }
+
+    /// A bordered panel block with a themed title.
+    pub(crate) fn block<'a>(&self, title: &'a str) -> Block<'a> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.border)
+            .title(title)
+            .title_style(self.title)
+    }
+}
+
+/// Return whether the user asked for no color via `NO_COLOR`.
+///
+/// Per <https://no-color.org>, any non-empty value disables color.
+fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
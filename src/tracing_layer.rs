@@ -0,0 +1,177 @@
+//! ==============================================================================
+//! src/tracing_layer.rs
+//! `tracing-subscriber` layer that maps progress spans to store clients.
+//! ==============================================================================
+
+use std::fmt::{self, Write as _};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{ClientHandle, ClientReporter};
+
+/// Span field whose presence marks a span as a progress client.
+const FIELD_TOTAL: &str = "progress.total";
+/// Span field holding the current completed units.
+const FIELD_CURRENT: &str = "progress.current";
+/// Optional span field overriding the client label.
+const FIELD_LABEL: &str = "progress.label";
+
+/// Layer that feeds a `ClientStore` from `tracing` spans and events.
+///
+/// Any span declaring a `progress.total` field becomes a client; leave it
+/// `tracing::field::Empty` for an indeterminate total. Recording
+/// `progress.current` updates progress, events inside the span become the
+/// client's log messages, and closing the span marks it completed. An
+/// optional `progress.label` field overrides the span name as the label.
+///
+/// To show these clients in a launched run, create the store yourself and
+/// pass it to `Launcher::store`. Events the store can no longer take are
+/// printed to stderr.
+pub struct ProgressLayer {
+    reporter: ClientReporter,
+}
+
+impl ProgressLayer {
+    /// Create a layer that reports into the store behind `reporter`.
+    pub fn new(reporter: ClientReporter) -> Self {
+        Self { reporter }
+    }
+}
+
+impl<S> Layer<S> for ProgressLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().fields().field(FIELD_TOTAL).is_none() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = ProgressFields::default();
+        attrs.record(&mut fields);
+        let label = fields
+            .label
+            .unwrap_or_else(|| attrs.metadata().name().to_string());
+
+        if let Ok(handle) = self.reporter.start(label, fields.total) {
+            if let Some(current) = fields.current {
+                let _ = handle.set_current(current);
+            }
+            span.extensions_mut().insert(handle);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(handle) = extensions.get::<ClientHandle>() else {
+            return;
+        };
+
+        let mut fields = ProgressFields::default();
+        values.record(&mut fields);
+        if let Some(label) = fields.label {
+            let _ = handle.set_label(label);
+        }
+        if fields.total.is_some() {
+            let _ = handle.set_total(fields.total);
+        }
+        if let Some(current) = fields.current {
+            let _ = handle.set_current(current);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        for span in scope {
+            let extensions = span.extensions();
+            if let Some(handle) = extensions.get::<ClientHandle>() {
+                let mut message = EventMessage::default();
+                event.record(&mut message);
+                let message = format!("{} {}", event.metadata().level(), message.text);
+                if handle.log(message.clone()).is_err() {
+                    eprintln!("{message}");
+                }
+                return;
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(handle) = span.extensions().get::<ClientHandle>() {
+                let _ = handle.complete();
+            }
+        }
+    }
+}
+
+/// Visitor collecting the designated progress fields.
+#[derive(Default)]
+struct ProgressFields {
+    total: Option<u64>,
+    current: Option<u64>,
+    label: Option<String>,
+}
+
+impl ProgressFields {
+    fn set_number(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            FIELD_TOTAL => self.total = Some(value),
+            FIELD_CURRENT => self.current = Some(value),
+            _ => {}
+        }
+    }
+}
+
+impl Visit for ProgressFields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set_number(field, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set_number(field, value.max(0) as u64);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == FIELD_LABEL {
+            self.label = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == FIELD_LABEL {
+            self.label = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Visitor rendering an event as `message key=value ...`.
+#[derive(Default)]
+struct EventMessage {
+    text: String,
+}
+
+impl Visit for EventMessage {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.text.is_empty() {
+            self.text.push(' ');
+        }
+        if field.name() == "message" {
+            let _ = write!(self.text, "{value:?}");
+        } else {
+            let _ = write!(self.text, "{}={value:?}", field.name());
+        }
+    }
+}